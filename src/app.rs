@@ -0,0 +1,147 @@
+use std::io;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Server, ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{web::Data, App, Error, HttpServer};
+use sea_orm::DatabaseConnection;
+
+use crate::config::api_version::ApiVersionSettings;
+use crate::config::cors::CorsSettings;
+use crate::config::database::ReadReplica;
+use crate::config::field_case::FieldCase;
+use crate::config::metrics::MetricsAccess;
+use crate::config::security_headers::SecurityHeadersSettings;
+use crate::middleware;
+use crate::middleware::cors::cors_config;
+use crate::repositories::SeaOrmUserRepository;
+use crate::routes;
+use crate::services::{email_sender_from_env, UserService};
+use crate::utils::lifecycle::Lifecycle;
+
+/// Estado compartido por todos los workers.
+#[derive(Clone)]
+pub struct AppState {
+    pub db: DatabaseConnection,
+    pub replica: ReadReplica,
+    pub lifecycle: Data<Lifecycle>,
+    /// Servicio de usuarios; se puede construir sobre otro `UserRepository`.
+    pub usuarios: Data<UserService>,
+}
+
+impl AppState {
+    /// Estado con los usuarios guardados en la base de datos.
+    pub fn new(db: DatabaseConnection, replica: ReadReplica, lifecycle: Data<Lifecycle>) -> Self {
+        let mut usuarios = UserService::new(SeaOrmUserRepository::new(db.clone(), replica.clone()));
+        if let Some(correo) = email_sender_from_env() {
+            usuarios = usuarios.con_correo(correo);
+        }
+        Self { db, replica, lifecycle, usuarios: Data::new(usuarios) }
+    }
+}
+
+/// Configuración de los middlewares y rutas de la aplicación.
+#[derive(Clone)]
+pub struct AppConfig {
+    pub cors: CorsSettings,
+    pub cabeceras_seguridad: SecurityHeadersSettings,
+    pub versiones: ApiVersionSettings,
+    /// Nombres de campo de las respuestas desde la v2.
+    pub campos: FieldCase,
+    /// Acceso a `/metrics` en el puerto de la API.
+    pub metricas: MetricsAccess,
+}
+
+impl AppConfig {
+    pub fn from_env() -> Self {
+        Self {
+            cors: CorsSettings::from_env(),
+            cabeceras_seguridad: SecurityHeadersSettings::from_env(),
+            versiones: ApiVersionSettings::from_env(),
+            campos: FieldCase::from_env(),
+            metricas: MetricsAccess::from_env(),
+        }
+    }
+}
+
+/// Ajustes del servidor HTTP que sirve la aplicación.
+pub struct ServerSettings {
+    pub app: AppConfig,
+    /// Tiempo máximo para drenar las solicitudes en curso al detenerlo.
+    pub timeout_apagado: Duration,
+    /// Con TLS se negocia HTTP/2 por ALPN y se guarda el certificado de cliente.
+    #[cfg(feature = "tls")]
+    pub tls: Option<rustls::ServerConfig>,
+}
+
+impl ServerSettings {
+    pub fn new(app: AppConfig) -> Self {
+        Self {
+            app,
+            timeout_apagado: Duration::from_secs(30),
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+/// Construye la aplicación con todas las rutas y middlewares.
+pub fn build_app(
+    estado: &AppState,
+    config: &AppConfig,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = Error,
+        InitError = (),
+    >,
+> {
+    let metricas_en_app = config.metricas.en_app();
+
+    App::new()
+        .app_data(Data::new(estado.db.clone()))
+        .app_data(Data::new(estado.replica.clone()))
+        .app_data(estado.lifecycle.clone())
+        .app_data(estado.usuarios.clone())
+        .wrap(middleware::auth::Authentication::new(config.metricas.clone()))
+        // CORS va por fuera de la autenticación: los preflight OPTIONS no llevan token
+        .wrap(cors_config(&config.cors))
+        .wrap(middleware::security_headers::SecurityHeaders::new(config.cabeceras_seguridad.clone()))
+        .wrap(middleware::metrics::Metrics)
+        // Reescribe `/api/...` a `/api/v{n}/...` antes de que las métricas busquen el patrón
+        .wrap(middleware::api_version::ApiVersioning::new(config.versiones.clone(), config.campos))
+        // Por fuera del resto para que sus errores (401, 406) también se traduzcan
+        .wrap(middleware::locale::Localization)
+        .wrap(middleware::request_id::RequestIdentifier)
+        .configure(routes::config::config_routes)
+        .configure(move |cfg| {
+            if metricas_en_app {
+                routes::metrics_routes::config(cfg);
+            }
+        })
+}
+
+/// Sirve la aplicación sobre un listener ya enlazado (puerto 0 en los tests).
+///
+/// No atiende señales: quien lo llama detiene el servidor con `Server::handle`.
+pub fn run(estado: AppState, ajustes: ServerSettings, listener: TcpListener) -> io::Result<Server> {
+    let config = ajustes.app;
+    let servidor = HttpServer::new(move || build_app(&estado, &config))
+        .disable_signals()
+        .shutdown_timeout(ajustes.timeout_apagado.as_secs());
+
+    #[cfg(feature = "tls")]
+    let servidor = match ajustes.tls {
+        Some(tls) => servidor
+            .on_connect(crate::utils::tls::registrar_certificado_cliente)
+            .listen_rustls_0_23(listener, tls)?,
+        None => servidor.listen(listener)?,
+    };
+    #[cfg(not(feature = "tls"))]
+    let servidor = servidor.listen(listener)?;
+
+    Ok(servidor.run())
+}
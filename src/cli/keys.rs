@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use serde::Serialize;
+
+use super::{imprimir, Formato, Resultado};
+use crate::config::env::var_opcional;
+use crate::utils::jwt::rotar_claves;
+
+#[derive(Debug, Subcommand)]
+pub enum JwtCommand {
+    /// Genera una clave de firma nueva y la activa
+    Rotate {
+        /// Archivo de claves (por defecto `JWT_KEYS_FILE`)
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Claves que se conservan, incluida la nueva; las anteriores siguen
+        /// validando los tokens emitidos antes de la rotación
+        #[arg(long, default_value_t = 2)]
+        keep: usize,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Rotacion {
+    archivo: String,
+    activa: String,
+    claves: Vec<String>,
+}
+
+pub async fn ejecutar(comando: JwtCommand, formato: Formato) -> Resultado {
+    let JwtCommand::Rotate { file, keep } = comando;
+    let ruta = file
+        .or_else(|| var_opcional("JWT_KEYS_FILE").map(PathBuf::from))
+        .ok_or("Indica el archivo de claves con --file o JWT_KEYS_FILE")?;
+
+    let claves = rotar_claves(&ruta, keep)?;
+    let rotacion = Rotacion {
+        archivo: ruta.display().to_string(),
+        activa: claves.activa,
+        claves: claves.claves.into_iter().map(|clave| clave.kid).collect(),
+    };
+
+    imprimir(formato, &rotacion, |r| {
+        format!(
+            "Clave activa: {} (conservadas: {}) en {}",
+            r.activa,
+            r.claves.join(", "),
+            r.archivo
+        )
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use clap::Subcommand;
+use sea_orm_migration::{MigrationStatus, MigratorTrait};
+use serde::Serialize;
+
+use super::{imprimir, Formato, Resultado};
+use crate::config::database;
+use crate::migrations::Migrator;
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Aplica las migraciones pendientes
+    Up,
+    /// Muestra qué migraciones están aplicadas y cuáles pendientes
+    Status,
+}
+
+#[derive(Debug, Serialize)]
+struct EstadoMigracion {
+    nombre: String,
+    aplicada: bool,
+}
+
+pub async fn ejecutar(comando: MigrateCommand, formato: Formato) -> Resultado {
+    let db = database::connect_without_migrations().await?;
+
+    if let MigrateCommand::Up = comando {
+        let pendientes = Migrator::get_pending_migrations(&db).await?.len();
+        Migrator::up(&db, None).await?;
+        if formato == Formato::Human {
+            println!("Migraciones aplicadas: {}", pendientes);
+        }
+    }
+
+    let estado: Vec<EstadoMigracion> = Migrator::get_migration_with_status(&db)
+        .await?
+        .into_iter()
+        .map(|migracion| EstadoMigracion {
+            nombre: migracion.name().to_string(),
+            aplicada: matches!(migracion.status(), MigrationStatus::Applied),
+        })
+        .collect();
+
+    imprimir(formato, &estado, |estado| {
+        estado
+            .iter()
+            .map(|m| format!("[{}] {}", if m.aplicada { "x" } else { " " }, m.nombre))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+
+    Ok(())
+}
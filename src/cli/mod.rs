@@ -0,0 +1,104 @@
+mod keys;
+mod migrations;
+mod seed;
+mod users;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use sea_orm::DatabaseConnection;
+use sea_orm_migration::MigratorTrait;
+use serde::Serialize;
+
+use crate::config::database;
+use crate::migrations::Migrator;
+
+/// API de usuarios y herramientas de administración.
+///
+/// Sin subcomando se inicia el servidor HTTP.
+#[derive(Debug, Parser)]
+#[command(name = "rust-api", version)]
+pub struct Cli {
+    /// Formato de salida de los comandos de administración
+    #[arg(long, value_enum, default_value_t = Formato::Human, global = true)]
+    pub format: Formato,
+
+    #[command(subcommand)]
+    pub comando: Option<Comando>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Formato {
+    Human,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Comando {
+    /// Inicia el servidor HTTP (por defecto)
+    Serve,
+    /// Gestión de usuarios
+    #[command(subcommand)]
+    Users(users::UsersCommand),
+    /// Migraciones de la base de datos
+    #[command(subcommand)]
+    Migrate(migrations::MigrateCommand),
+    /// Crea usuarios de demostración (no disponible en producción)
+    Seed,
+    /// Claves de firma de los tokens JWT
+    #[command(subcommand)]
+    Jwt(keys::JwtCommand),
+}
+
+type Resultado = Result<(), Box<dyn std::error::Error>>;
+
+/// Ejecuta un comando de administración y devuelve el código de salida.
+pub async fn ejecutar(comando: Comando, formato: Formato) -> i32 {
+    // Los logs van a stderr para no mezclarse con la salida (p. ej. JSON)
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(std::env::var("RUST_LOG").unwrap_or_else(|_| "warn".into()))
+        .without_time()
+        .init();
+
+    let resultado = match comando {
+        Comando::Serve => unreachable!("`serve` lo atiende `main`"),
+        Comando::Users(comando) => users::ejecutar(comando, formato).await,
+        Comando::Migrate(comando) => migrations::ejecutar(comando, formato).await,
+        Comando::Seed => seed::ejecutar(formato).await,
+        Comando::Jwt(comando) => keys::ejecutar(comando, formato).await,
+    };
+
+    match resultado {
+        Ok(()) => 0,
+        Err(e) => {
+            match formato {
+                Formato::Human => eprintln!("Error: {}", e),
+                Formato::Json => eprintln!("{}", serde_json::json!({ "error": e.to_string() })),
+            }
+            1
+        }
+    }
+}
+
+// Imprime `valor` como JSON o con el formato legible de `humano`
+fn imprimir<T: Serialize>(formato: Formato, valor: &T, humano: impl FnOnce(&T) -> String) {
+    match formato {
+        Formato::Human => println!("{}", humano(valor)),
+        Formato::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(valor).unwrap_or_else(|e| e.to_string())
+        ),
+    }
+}
+
+// Conexión de los comandos que leen o escriben datos. No aplica migraciones
+// como el servidor: con migraciones pendientes falla y remite a `migrate up`
+async fn conectar() -> Result<DatabaseConnection, Box<dyn std::error::Error>> {
+    let db = database::connect_without_migrations().await?;
+
+    let pendientes = Migrator::get_pending_migrations(&db).await?.len();
+    if pendientes > 0 {
+        return Err(format!("Hay {} migraciones pendientes; aplícalas con `migrate up`", pendientes).into());
+    }
+
+    Ok(db)
+}
@@ -0,0 +1,57 @@
+use sea_orm::{ColumnTrait, QueryFilter};
+use serde::Serialize;
+
+use super::{conectar, imprimir, users, Formato, Resultado};
+use crate::config::environment::Environment;
+use crate::models::user::{self, Entity as UserEntity};
+
+// Usuarios de demostración: (nombre, email, contraseña, admin)
+const USUARIOS_DEMO: [(&str, &str, &str, bool); 3] = [
+    ("Administrador Demo", "admin@demo.local", "Panel-Control-2026", true),
+    ("Ana Torres", "ana@demo.local", "Girasol-Verde-77", false),
+    ("Luis Romero", "luis@demo.local", "Montana-Azul-58", false),
+];
+
+#[derive(Debug, Serialize)]
+struct UsuarioDemo {
+    email: &'static str,
+    password: &'static str,
+    creado: bool,
+}
+
+/// Crea los usuarios de demostración que falten.
+pub async fn ejecutar(formato: Formato) -> Resultado {
+    if Environment::from_env().is_production() {
+        return Err("No se cargan datos de demostración con APP_ENV=production".into());
+    }
+
+    let db = conectar().await?;
+    let mut resultado = Vec::new();
+
+    for (nombre, email, contraseña, admin) in USUARIOS_DEMO {
+        // Un usuario eliminado sigue ocupando su email
+        let existe = UserEntity::with_deleted()
+            .filter(user::Column::Email.eq(email))
+            .one(&db)
+            .await?
+            .is_some();
+
+        if !existe {
+            users::crear(&db, nombre, email, contraseña, admin).await?;
+        }
+        resultado.push(UsuarioDemo { email, password: contraseña, creado: !existe });
+    }
+
+    imprimir(formato, &resultado, |usuarios| {
+        usuarios
+            .iter()
+            .map(|u| {
+                let estado = if u.creado { "creado" } else { "ya existía" };
+                format!("{} / {} ({})", u.email, u.password, estado)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+
+    Ok(())
+}
@@ -0,0 +1,375 @@
+use std::io::IsTerminal;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use serde::Serialize;
+
+use super::{conectar, imprimir, Formato, Resultado};
+use crate::config::database::ReadReplica;
+use crate::errors::api_error::ApiError;
+use crate::models::audit_log;
+use crate::models::user::{self, CreateUserDto, Entity as UserEntity, Model as UserModel, UpdateUserDto};
+use crate::repositories::SeaOrmUserRepository;
+use crate::services::UserService;
+use crate::utils::audit::{self, AuditEvent};
+
+// La contraseña de `create` y `reset-password` no es un argumento: quedaría en
+// el historial de la shell y a la vista en `ps`. Se lee de `USER_PASSWORD` o de
+// la entrada estándar
+#[derive(Debug, Subcommand)]
+pub enum UsersCommand {
+    /// Crea un usuario (con `--admin`, un administrador). La contraseña se lee de
+    /// `USER_PASSWORD` o de la entrada estándar
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        admin: bool,
+    },
+    /// Cambia la contraseña de un usuario, leída de `USER_PASSWORD` o de la
+    /// entrada estándar
+    ResetPassword {
+        #[command(flatten)]
+        usuario: Usuario,
+    },
+    /// Lista los usuarios
+    List {
+        /// Incluye los usuarios eliminados
+        #[arg(long)]
+        all: bool,
+    },
+    /// Impide que un usuario inicie sesión sin borrar sus datos
+    Disable {
+        #[command(flatten)]
+        usuario: Usuario,
+    },
+    /// Vuelve a habilitar un usuario deshabilitado
+    Enable {
+        #[command(flatten)]
+        usuario: Usuario,
+    },
+    /// Elimina un usuario (borrado lógico salvo con `--hard`)
+    Delete {
+        #[command(flatten)]
+        usuario: Usuario,
+        /// Borra el usuario definitivamente
+        #[arg(long)]
+        hard: bool,
+    },
+}
+
+/// Usuario sobre el que actúa el comando: id numérico o email.
+#[derive(Debug, Args)]
+pub struct Usuario {
+    usuario: String,
+}
+
+/// Datos de un usuario que muestra la CLI (sin la contraseña).
+#[derive(Debug, Serialize)]
+struct UsuarioSalida {
+    id: i32,
+    name: String,
+    email: String,
+    role: String,
+    created_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+    disabled_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserModel> for UsuarioSalida {
+    fn from(usuario: UserModel) -> Self {
+        Self {
+            id: usuario.id,
+            name: usuario.name,
+            email: usuario.email,
+            role: usuario.role,
+            created_at: usuario.created_at,
+            deleted_at: usuario.deleted_at,
+            disabled_at: usuario.disabled_at,
+        }
+    }
+}
+
+impl UsuarioSalida {
+    fn estado(&self) -> &'static str {
+        if self.deleted_at.is_some() {
+            "eliminado"
+        } else if self.disabled_at.is_some() {
+            "deshabilitado"
+        } else {
+            "activo"
+        }
+    }
+
+    fn describir(&self) -> String {
+        format!(
+            "#{} {} <{}> rol={} estado={}",
+            self.id,
+            self.name,
+            self.email,
+            self.role,
+            self.estado()
+        )
+    }
+}
+
+pub async fn ejecutar(comando: UsersCommand, formato: Formato) -> Resultado {
+    let db = conectar().await?;
+
+    match comando {
+        UsersCommand::Create { name, email, admin } => {
+            let password = leer_contraseña()?;
+            let usuario = crear(&db, &name, &email, &password, admin).await?;
+            imprimir(formato, &UsuarioSalida::from(usuario), |u| format!("Usuario creado: {}", u.describir()));
+        }
+        UsersCommand::ResetPassword { usuario } => {
+            let usuario = buscar(&db, &usuario, false).await?;
+            let password = leer_contraseña()?;
+            let id = usuario.id;
+            let cambios = UpdateUserDto { name: None, email: None, password: Some(password), locale: None };
+            // La CLI tiene permisos de administrador
+            let usuario = servicio(&db).actualizar(usuario, &cambios, true).await.map_err(error_servicio)?;
+
+            audit::registrar_desde_cli(&db, AuditEvent::new(audit_log::PASSWORD_CHANGED).target(id)).await;
+            imprimir(formato, &UsuarioSalida::from(usuario), |u| {
+                format!("Contraseña cambiada: {}", u.describir())
+            });
+        }
+        UsersCommand::List { all } => {
+            let consulta = if all { UserEntity::with_deleted() } else { UserEntity::find() };
+            let usuarios: Vec<UsuarioSalida> = consulta
+                .order_by_asc(user::Column::Id)
+                .all(&db)
+                .await?
+                .into_iter()
+                .map(UsuarioSalida::from)
+                .collect();
+
+            imprimir(formato, &usuarios, |usuarios| tabla(usuarios));
+        }
+        UsersCommand::Disable { usuario } => {
+            let usuario = cambiar_estado(&db, &usuario, Some(Utc::now()), audit_log::USER_DISABLED).await?;
+            imprimir(formato, &usuario, |u| format!("Usuario deshabilitado: {}", u.describir()));
+        }
+        UsersCommand::Enable { usuario } => {
+            let usuario = cambiar_estado(&db, &usuario, None, audit_log::USER_ENABLED).await?;
+            imprimir(formato, &usuario, |u| format!("Usuario habilitado: {}", u.describir()));
+        }
+        UsersCommand::Delete { usuario, hard } => {
+            let usuario = buscar(&db, &usuario, hard).await?;
+            let id = usuario.id;
+
+            let usuario = if hard {
+                UserEntity::delete_by_id(id).exec(&db).await?;
+                UsuarioSalida::from(usuario)
+            } else {
+                let version = usuario.version;
+                let mut activo = usuario.into_active_model();
+                activo.deleted_at = Set(Some(Utc::now()));
+                activo.version = Set(version + 1);
+                activo.updated_at = Set(Utc::now());
+                UsuarioSalida::from(activo.update(&db).await?)
+            };
+
+            audit::registrar_desde_cli(&db, AuditEvent::new(audit_log::USER_DELETED).target(id)).await;
+            let mensaje = if hard { "Usuario eliminado definitivamente" } else { "Usuario eliminado" };
+            imprimir(formato, &usuario, |u| format!("{}: {}", mensaje, u.describir()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Crea un usuario con las mismas reglas que el registro por la API.
+pub async fn crear(
+    db: &DatabaseConnection,
+    nombre: &str,
+    email: &str,
+    contraseña: &str,
+    admin: bool,
+) -> Result<UserModel, Box<dyn std::error::Error>> {
+    let datos = CreateUserDto {
+        name: nombre.to_string(),
+        email: email.to_string(),
+        password: contraseña.to_string(),
+        locale: None,
+    };
+    let rol = if admin { user::ROLE_ADMIN } else { user::ROLE_USER };
+    let usuario = servicio(db).registrar(&datos, rol).await.map_err(error_servicio)?;
+
+    audit::registrar_desde_cli(
+        db,
+        AuditEvent::new(audit_log::USER_REGISTERED)
+            .target(usuario.id)
+            .cambios(serde_json::json!({ "name": usuario.name, "email": usuario.email, "role": usuario.role })),
+    )
+    .await;
+
+    Ok(usuario)
+}
+
+async fn cambiar_estado(
+    db: &DatabaseConnection,
+    usuario: &Usuario,
+    deshabilitado: Option<DateTime<Utc>>,
+    accion: &'static str,
+) -> Result<UsuarioSalida, Box<dyn std::error::Error>> {
+    let usuario = buscar(db, usuario, false).await?;
+    let id = usuario.id;
+    let version = usuario.version;
+
+    let mut activo = usuario.into_active_model();
+    activo.disabled_at = Set(deshabilitado);
+    activo.version = Set(version + 1);
+    activo.updated_at = Set(Utc::now());
+    let usuario = activo.update(db).await?;
+
+    audit::registrar_desde_cli(db, AuditEvent::new(accion).target(id)).await;
+    Ok(UsuarioSalida::from(usuario))
+}
+
+// Busca por id o por email; los eliminados solo con `incluir_eliminados`
+async fn buscar(
+    db: &DatabaseConnection,
+    usuario: &Usuario,
+    incluir_eliminados: bool,
+) -> Result<UserModel, Box<dyn std::error::Error>> {
+    let consulta = if incluir_eliminados { UserEntity::with_deleted() } else { UserEntity::find() };
+    let consulta = match usuario.usuario.parse::<i32>() {
+        Ok(id) => consulta.filter(user::Column::Id.eq(id)),
+        Err(_) => consulta.filter(user::Column::Email.eq(&usuario.usuario)),
+    };
+
+    consulta
+        .one(db)
+        .await?
+        .ok_or_else(|| format!("Usuario no encontrado: {}", usuario.usuario).into())
+}
+
+// `USER_PASSWORD` o una línea de la entrada estándar; en una terminal se pide
+// dos veces y sin eco
+fn leer_contraseña() -> Result<String, Box<dyn std::error::Error>> {
+    if let Ok(contraseña) = std::env::var("USER_PASSWORD") {
+        return Ok(contraseña);
+    }
+
+    let contraseña = if std::io::stdin().is_terminal() {
+        let contraseña = pedir_sin_eco("Contraseña: ")?;
+        if pedir_sin_eco("Repite la contraseña: ")? != contraseña {
+            return Err("Las contraseñas no coinciden".into());
+        }
+        contraseña
+    } else {
+        leer_linea()?
+    };
+
+    if contraseña.is_empty() {
+        return Err("Falta la contraseña: usa USER_PASSWORD o escríbela en la entrada estándar".into());
+    }
+    Ok(contraseña)
+}
+
+fn pedir_sin_eco(mensaje: &str) -> std::io::Result<String> {
+    eprint!("{}", mensaje);
+    let linea = {
+        let _sin_eco = SinEco::activar();
+        leer_linea()
+    };
+    eprintln!();
+    linea
+}
+
+fn leer_linea() -> std::io::Result<String> {
+    let mut linea = String::new();
+    std::io::stdin().read_line(&mut linea)?;
+    Ok(linea.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// Desactiva el eco de la terminal mientras vive y lo restaura al soltarse
+#[cfg(unix)]
+struct SinEco(Option<libc::termios>);
+
+#[cfg(unix)]
+impl SinEco {
+    fn activar() -> Self {
+        // SAFETY: `termios` es un struct C plano y `tcgetattr` lo rellena entero
+        // antes de usarlo; solo se modifica la terminal de la entrada estándar
+        unsafe {
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Self(None);
+            }
+            let mut sin_eco = original;
+            sin_eco.c_lflag &= !libc::ECHO;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &sin_eco);
+            Self(Some(original))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SinEco {
+    fn drop(&mut self) {
+        if let Some(original) = &self.0 {
+            // SAFETY: restaura la configuración leída en `activar`
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct SinEco;
+
+#[cfg(not(unix))]
+impl SinEco {
+    fn activar() -> Self {
+        Self
+    }
+}
+
+fn servicio(db: &DatabaseConnection) -> UserService {
+    UserService::new(SeaOrmUserRepository::new(db.clone(), ReadReplica::primaria(db.clone())))
+}
+
+// Los errores de validación llevan el detalle en `errores`, no en el mensaje
+fn error_servicio(error: ApiError) -> Box<dyn std::error::Error> {
+    if error.errores.is_empty() {
+        return error.mensaje.to_string().into();
+    }
+
+    let mensajes: Vec<String> = error.errores.into_iter().map(|error| error.mensaje.to_string()).collect();
+    mensajes.join("; ").into()
+}
+
+fn tabla(usuarios: &[UsuarioSalida]) -> String {
+    if usuarios.is_empty() {
+        return "No hay usuarios".to_string();
+    }
+
+    let ancho_nombre = usuarios.iter().map(|u| u.name.chars().count()).max().unwrap_or(0).max(6);
+    let ancho_email = usuarios.iter().map(|u| u.email.chars().count()).max().unwrap_or(0).max(5);
+
+    let mut lineas = vec![format!(
+        "{:>5}  {:<ancho_nombre$}  {:<ancho_email$}  {:<5}  ESTADO",
+        "ID", "NOMBRE", "EMAIL", "ROL"
+    )];
+    lineas.extend(usuarios.iter().map(|u| {
+        format!(
+            "{:>5}  {:<ancho_nombre$}  {:<ancho_email$}  {:<5}  {}",
+            u.id,
+            u.name,
+            u.email,
+            u.role,
+            u.estado()
+        )
+    }));
+    lineas.join("\n")
+}
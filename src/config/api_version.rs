@@ -0,0 +1,104 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::config::env::var_opcional;
+
+/// Versiones servidas bajo `/api/v{n}`.
+pub const VERSIONES: [u16; 2] = [1, 2];
+
+/// Versión de las rutas sin versión (`/api/usuarios`) cuando `Accept` no pide otra.
+pub const VERSION_POR_DEFECTO: u16 = 1;
+
+/// Primera versión que responde con el envoltorio `{ data, meta, links }`. Las
+/// anteriores mantienen el cuerpo de cada endpoint para los clientes existentes.
+pub const VERSION_ENVOLTORIO: u16 = 2;
+
+/// Tipo de medio con el que se negocia la versión:
+/// `Accept: application/vnd.api+json;version=2`.
+pub const TIPO_VERSIONADO: &str = "application/vnd.api+json";
+
+// Rutas bajo `/api` que no tienen versión: sondas y documentación
+const RUTAS_SIN_VERSION: [&str; 3] = ["/api/salud", "/api/openapi.json", "/api/docs"];
+
+/// Calendario de retirada de las versiones, leído de `API_V{n}_DEPRECATED_AT`,
+/// `API_V{n}_SUNSET_AT` y `API_DEPRECATION_LINK`.
+#[derive(Debug, Clone, Default)]
+pub struct ApiVersionSettings {
+    pub obsoletas: Vec<Obsolescencia>,
+    /// Documentación de la migración, enviada en `Link: <...>; rel="deprecation"`.
+    pub enlace: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Obsolescencia {
+    pub version: u16,
+    pub desde: DateTime<Utc>,
+    /// Fecha a partir de la cual la versión puede dejar de responder.
+    pub retirada: Option<DateTime<Utc>>,
+}
+
+impl ApiVersionSettings {
+    pub fn from_env() -> Self {
+        let obsoletas = VERSIONES
+            .into_iter()
+            .filter_map(|version| {
+                let desde = fecha(&format!("API_V{}_DEPRECATED_AT", version))?;
+                let retirada = fecha(&format!("API_V{}_SUNSET_AT", version));
+                Some(Obsolescencia { version, desde, retirada })
+            })
+            .collect();
+
+        Self { obsoletas, enlace: var_opcional("API_DEPRECATION_LINK") }
+    }
+
+    pub fn obsolescencia(&self, version: u16) -> Option<&Obsolescencia> {
+        self.obsoletas.iter().find(|o| o.version == version)
+    }
+}
+
+/// Separa `/api/v{n}/resto` en la versión y `/resto`.
+pub fn separar_version(path: &str) -> Option<(u16, &str)> {
+    let resto = path.strip_prefix("/api/v")?;
+    let fin = resto.find('/').unwrap_or(resto.len());
+    let version = resto[..fin].parse().ok()?;
+    Some((version, &resto[fin..]))
+}
+
+/// `true` para las rutas de `/api` que se sirven en cada versión.
+pub fn es_ruta_versionada(path: &str) -> bool {
+    let Some(resto) = path.strip_prefix("/api") else {
+        return false;
+    };
+    let sin_version = RUTAS_SIN_VERSION.iter().any(|ruta| {
+        path.strip_prefix(ruta)
+            .is_some_and(|resto| resto.is_empty() || resto.starts_with('/'))
+    });
+
+    resto.starts_with('/') && !sin_version && separar_version(path).is_none()
+}
+
+/// Ruta equivalente sin versión: `/api/v2/auth/login` → `/api/auth/login`.
+pub fn sin_version(path: &str) -> std::borrow::Cow<'_, str> {
+    match separar_version(path) {
+        Some((_, resto)) => format!("/api{}", resto).into(),
+        None => path.into(),
+    }
+}
+
+// Acepta `2027-06-30` o una fecha RFC 3339
+fn fecha(variable: &str) -> Option<DateTime<Utc>> {
+    let valor = var_opcional(variable)?;
+    let fecha = DateTime::parse_from_rfc3339(&valor)
+        .map(|fecha| fecha.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(&valor, "%Y-%m-%d")
+                .ok()
+                .and_then(|dia| dia.and_hms_opt(0, 0, 0))
+                .map(|momento| momento.and_utc())
+        });
+
+    if fecha.is_none() {
+        tracing::warn!("{} no es una fecha válida: {}", variable, valor);
+    }
+    fecha
+}
@@ -0,0 +1,140 @@
+use actix_web::http::{header::HeaderName, Method};
+
+use crate::config::env::{var_bool, var_o, var_opcional};
+use crate::config::environment::Environment;
+
+const METODOS_DEFECTO: &str = "GET,POST,PUT,PATCH,DELETE,OPTIONS";
+const CABECERAS_PRODUCCION: &str =
+    "Authorization,Content-Type,Accept,Accept-Language,If-Match,If-None-Match,X-Request-Id,traceparent";
+const CABECERAS_EXPUESTAS_DEFECTO: &str = "ETag,X-Request-Id,traceparent,Api-Version,Deprecation,Sunset,Link";
+
+/// Política CORS, leída de variables de entorno `CORS_*`.
+///
+/// En desarrollo se permite cualquier origen y cabecera; en producción no se
+/// permite ningún origen salvo los de `CORS_ALLOWED_ORIGINS`.
+#[derive(Debug, Clone)]
+pub struct CorsSettings {
+    pub origenes: Vec<OriginPattern>,
+    pub cualquier_origen: bool,
+    pub metodos: Vec<String>,
+    /// `None` permite cualquier cabecera.
+    pub cabeceras: Option<Vec<String>>,
+    pub cabeceras_expuestas: Vec<String>,
+    pub max_age: usize,
+    pub credenciales: bool,
+    pub entorno: Environment,
+}
+
+/// Origen permitido: exacto (`https://app.ejemplo.com`) o con comodín de
+/// subdominio (`https://*.ejemplo.com`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    Exacto(String),
+    Subdominio { prefijo: String, sufijo: String },
+}
+
+impl CorsSettings {
+    pub fn from_env() -> Self {
+        let entorno = Environment::from_env();
+        let produccion = entorno.is_production();
+
+        let origenes_defecto = if produccion { "" } else { "*" };
+        let origenes = lista(var_opcional("CORS_ALLOWED_ORIGINS").as_deref().unwrap_or(origenes_defecto));
+        let cualquier_origen = origenes.iter().any(|origen| origen == "*");
+
+        let cabeceras_defecto = if produccion { CABECERAS_PRODUCCION } else { "*" };
+        let cabeceras = lista(var_opcional("CORS_ALLOWED_HEADERS").as_deref().unwrap_or(cabeceras_defecto));
+
+        Self {
+            origenes: origenes
+                .iter()
+                .filter(|origen| *origen != "*")
+                .map(|origen| OriginPattern::parse(origen))
+                .collect(),
+            cualquier_origen,
+            metodos: lista(var_opcional("CORS_ALLOWED_METHODS").as_deref().unwrap_or(METODOS_DEFECTO)),
+            cabeceras: if cabeceras.iter().any(|cabecera| cabecera == "*") {
+                None
+            } else {
+                Some(cabeceras)
+            },
+            cabeceras_expuestas: lista(
+                var_opcional("CORS_EXPOSED_HEADERS").as_deref().unwrap_or(CABECERAS_EXPUESTAS_DEFECTO),
+            ),
+            max_age: var_o("CORS_MAX_AGE_SECS", 3600),
+            credenciales: var_bool("CORS_ALLOW_CREDENTIALS", true),
+            entorno,
+        }
+    }
+
+    /// Comprueba la configuración antes de arrancar el servidor.
+    pub fn validar(&self) -> Result<(), String> {
+        if self.entorno.is_production() && self.cualquier_origen && self.credenciales {
+            return Err(
+                "CORS_ALLOWED_ORIGINS=* no se puede combinar con CORS_ALLOW_CREDENTIALS=true en producción"
+                    .to_string(),
+            );
+        }
+
+        if let Some(metodo) = self.metodos.iter().find(|m| Method::from_bytes(m.as_bytes()).is_err()) {
+            return Err(format!("Método CORS inválido: {}", metodo));
+        }
+
+        let cabeceras = self.cabeceras.iter().flatten().chain(&self.cabeceras_expuestas);
+        for cabecera in cabeceras {
+            if HeaderName::from_bytes(cabecera.as_bytes()).is_err() {
+                return Err(format!("Cabecera CORS inválida: {}", cabecera));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `true` si el origen está en la lista permitida.
+    pub fn origen_permitido(&self, origen: &str) -> bool {
+        self.cualquier_origen || self.origenes.iter().any(|patron| patron.coincide(origen))
+    }
+}
+
+impl OriginPattern {
+    pub fn parse(origen: &str) -> Self {
+        let origen = origen.trim_end_matches('/').to_lowercase();
+
+        match origen.split_once("://*.") {
+            Some((esquema, dominio)) => Self::Subdominio {
+                prefijo: format!("{}://", esquema),
+                sufijo: format!(".{}", dominio),
+            },
+            None => Self::Exacto(origen),
+        }
+    }
+
+    pub fn coincide(&self, origen: &str) -> bool {
+        let origen = origen.to_lowercase();
+
+        match self {
+            Self::Exacto(permitido) => origen == *permitido,
+            Self::Subdominio { prefijo, sufijo } => origen
+                .strip_prefix(prefijo.as_str())
+                .and_then(|resto| resto.strip_suffix(sufijo.as_str()))
+                .is_some_and(|subdominio| subdominio.split('.').all(es_etiqueta_dns)),
+        }
+    }
+}
+
+// Una etiqueta de un nombre de dominio: `app` o `mi-app`, pero no `` ni `-app`
+fn es_etiqueta_dns(etiqueta: &str) -> bool {
+    !etiqueta.is_empty()
+        && !etiqueta.starts_with('-')
+        && !etiqueta.ends_with('-')
+        && etiqueta.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn lista(valor: &str) -> Vec<String> {
+    valor
+        .split(',')
+        .map(str::trim)
+        .filter(|elemento| !elemento.is_empty())
+        .map(str::to_string)
+        .collect()
+}
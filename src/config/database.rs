@@ -1,31 +1,255 @@
-use sea_orm::{Database, DatabaseConnection, DbErr, Schema, ConnectionTrait};
-use crate::models::user::Entity as UserEntity;
+use std::time::Duration;
+
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr};
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql"))]
+use sea_orm::DbBackend;
+use serde::Serialize;
+use sea_orm_migration::MigratorTrait;
+use crate::config::env::{var_bool, var_o, var_opcional};
+use crate::migrations::Migrator;
+
+// Una base SQLite en memoria vive mientras viva alguna conexión del pool
+const VIDA_SQLITE_MEMORIA: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+const ESPERA_MAXIMA_REINTENTO: Duration = Duration::from_secs(30);
+
+/// Ajustes del pool de conexiones, leídos de variables de entorno `DB_*`.
+#[derive(Debug, Clone)]
+pub struct DatabaseSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout: Duration,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Duration,
+    pub statement_timeout: Option<Duration>,
+    pub sql_logging: bool,
+    pub sql_log_level: log::LevelFilter,
+    pub slow_query_threshold: Option<Duration>,
+    pub connect_retries: u32,
+    pub connect_backoff: Duration,
+}
+
+impl DatabaseSettings {
+    pub fn from_env() -> Self {
+        let segundos = |nombre, defecto| Duration::from_secs(var_o(nombre, defecto));
+
+        Self {
+            max_connections: var_o("DB_MAX_CONNECTIONS", 10),
+            min_connections: var_o("DB_MIN_CONNECTIONS", 1),
+            connect_timeout: segundos("DB_CONNECT_TIMEOUT_SECS", 10),
+            acquire_timeout: segundos("DB_ACQUIRE_TIMEOUT_SECS", 30),
+            idle_timeout: segundos("DB_IDLE_TIMEOUT_SECS", 600),
+            statement_timeout: var_opcional("DB_STATEMENT_TIMEOUT_SECS")
+                .and_then(|valor| valor.parse().ok())
+                .map(Duration::from_secs),
+            sql_logging: var_bool("DB_SQL_LOGGING", true),
+            sql_log_level: var_o("DB_SQL_LOG_LEVEL", log::LevelFilter::Info),
+            slow_query_threshold: var_opcional("DB_SLOW_QUERY_MS")
+                .and_then(|valor| valor.parse().ok())
+                .map(Duration::from_millis),
+            connect_retries: var_o("DB_CONNECT_RETRIES", 5),
+            connect_backoff: Duration::from_millis(var_o("DB_CONNECT_BACKOFF_MS", 500)),
+        }
+    }
+
+    fn opciones(&self, database_url: &str) -> ConnectOptions {
+        let mut opciones = ConnectOptions::new(database_url);
+
+        opciones
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections.min(self.max_connections))
+            .connect_timeout(self.connect_timeout)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+            .sqlx_logging(self.sql_logging)
+            .sqlx_logging_level(self.sql_log_level);
+
+        if let Some(umbral) = self.slow_query_threshold {
+            opciones.sqlx_slow_statements_logging_settings(log::LevelFilter::Warn, umbral);
+        }
+
+        if let Some(limite) = self.statement_timeout {
+            aplicar_statement_timeout(&mut opciones, database_url, limite);
+        }
+
+        if es_sqlite_en_memoria(database_url) {
+            // Una sola conexión que no se recicla: cada conexión nueva vería una base vacía
+            opciones
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(VIDA_SQLITE_MEMORIA)
+                .max_lifetime(VIDA_SQLITE_MEMORIA);
+        }
+
+        opciones
+    }
+}
+
+/// Conexión usada por los handlers de solo lectura. Apunta a la réplica
+/// configurada en `DATABASE_REPLICA_URL` o, si no hay, a la primaria.
+#[derive(Clone)]
+pub struct ReadReplica(DatabaseConnection);
+
+impl ReadReplica {
+    /// Sin réplica: las lecturas van a la misma conexión primaria.
+    pub fn primaria(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub fn conexion(&self) -> &DatabaseConnection {
+        &self.0
+    }
+}
+
+/// Ocupación del pool de conexiones.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PoolStats {
+    pub tamano: u32,
+    pub inactivas: u32,
+    pub en_uso: u32,
+    pub maximo: u32,
+}
+
+impl PoolStats {
+    /// Fracción de conexiones en uso respecto al máximo (0.0 a 1.0).
+    pub fn saturacion(&self) -> f64 {
+        if self.maximo == 0 {
+            return 0.0;
+        }
+        f64::from(self.en_uso) / f64::from(self.maximo)
+    }
+
+    // Solo existe con algún backend: sin ellos no hay pools de sqlx que leer
+    #[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql"))]
+    fn de<DB: sea_orm::sqlx::Database>(pool: &sea_orm::sqlx::Pool<DB>) -> Self {
+        let tamano = pool.size();
+        let inactivas = pool.num_idle() as u32;
+
+        Self {
+            tamano,
+            inactivas,
+            en_uso: tamano.saturating_sub(inactivas),
+            maximo: pool.options().get_max_connections(),
+        }
+    }
+}
+
+pub fn estadisticas_pool(db: &DatabaseConnection) -> Option<PoolStats> {
+    match db.get_database_backend() {
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => Some(PoolStats::de(db.get_postgres_connection_pool())),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => Some(PoolStats::de(db.get_sqlite_connection_pool())),
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => Some(PoolStats::de(db.get_mysql_connection_pool())),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
 
 pub async fn connect() -> Result<DatabaseConnection, DbErr> {
     let database_url = std::env::var("DATABASE_URL")
         .expect("DATABASE_URL debe estar configurada en el archivo .env");
     
-    let db = Database::connect(&database_url).await?;
+    connect_to(&database_url).await
+}
+
+/// Conecta a la URL indicada (`postgres://`, `mysql://`, `sqlite://archivo.db`
+/// o `sqlite::memory:`) y aplica las migraciones pendientes.
+pub async fn connect_to(database_url: &str) -> Result<DatabaseConnection, DbErr> {
+    let ajustes = DatabaseSettings::from_env();
+    let db = conectar_con_reintentos(&ajustes, database_url, "primaria").await?;
     
-    // Auto-crear tablas si no existen
+    // Aplicar migraciones pendientes
     setup_tables(&db).await?;
     
     Ok(db)
 }
 
-async fn setup_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
-    let builder = db.get_database_backend();
-    let schema = Schema::new(builder);
-    
-    // Crear tabla de usuarios si no existe
-    let stmt = builder.build(
-        schema.create_table_from_entity(UserEntity).if_not_exists()
-    );
-    
-    match db.execute(stmt).await {
-        Ok(_) => tracing::info!("Tablas verificadas/creadas exitosamente"),
-        Err(e) => tracing::warn!("Error al crear tablas: {}", e),
+/// Conecta a `DATABASE_URL` sin aplicar migraciones, para inspeccionarlas.
+pub async fn connect_without_migrations() -> Result<DatabaseConnection, DbErr> {
+    let database_url = std::env::var("DATABASE_URL")
+        .expect("DATABASE_URL debe estar configurada en el archivo .env");
+
+    conectar_con_reintentos(&DatabaseSettings::from_env(), &database_url, "primaria").await
+}
+
+/// Conecta a la réplica de lectura si `DATABASE_REPLICA_URL` está configurada.
+pub async fn connect_replica(primaria: &DatabaseConnection) -> Result<ReadReplica, DbErr> {
+    match var_opcional("DATABASE_REPLICA_URL") {
+        Some(url) => {
+            let ajustes = DatabaseSettings::from_env();
+            let replica = conectar_con_reintentos(&ajustes, &url, "réplica").await?;
+            Ok(ReadReplica(replica))
+        }
+        None => Ok(ReadReplica::primaria(primaria.clone())),
     }
-    
+}
+
+// Reintenta con espera exponencial para tolerar que la base tarde en arrancar
+async fn conectar_con_reintentos(
+    ajustes: &DatabaseSettings,
+    database_url: &str,
+    nombre: &str,
+) -> Result<DatabaseConnection, DbErr> {
+    let mut espera = ajustes.connect_backoff;
+    let mut intento = 0;
+
+    loop {
+        match Database::connect(ajustes.opciones(database_url)).await {
+            #[cfg(feature = "otel")]
+            Ok(mut db) => {
+                crate::utils::telemetry::instrumentar_consultas(&mut db);
+                return Ok(db);
+            }
+            #[cfg(not(feature = "otel"))]
+            Ok(db) => return Ok(db),
+            Err(e) if intento < ajustes.connect_retries => {
+                intento += 1;
+                tracing::warn!(
+                    "No se pudo conectar a la base de datos {} (intento {}/{}): {}. Reintentando en {:?}",
+                    nombre,
+                    intento,
+                    ajustes.connect_retries,
+                    e,
+                    espera
+                );
+                tokio::time::sleep(espera).await;
+                espera = (espera * 2).min(ESPERA_MAXIMA_REINTENTO);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn aplicar_statement_timeout(opciones: &mut ConnectOptions, database_url: &str, limite: Duration) {
+    #[cfg(feature = "postgres")]
+    if database_url.starts_with("postgres") {
+        let milisegundos = limite.as_millis().to_string();
+        opciones.map_sqlx_postgres_opts(move |pg| {
+            pg.options([("statement_timeout", milisegundos.as_str())])
+        });
+        return;
+    }
+
+    #[cfg(feature = "sqlite")]
+    if database_url.starts_with("sqlite") {
+        // SQLite no tiene límite por sentencia; se limita la espera por bloqueos
+        opciones.map_sqlx_sqlite_opts(move |sqlite| sqlite.busy_timeout(limite));
+        return;
+    }
+
+    let _ = (opciones, limite);
+    tracing::warn!("DB_STATEMENT_TIMEOUT_SECS no está soportado para {}", database_url.split(':').next().unwrap_or_default());
+}
+
+fn es_sqlite_en_memoria(database_url: &str) -> bool {
+    database_url.starts_with("sqlite:")
+        && (database_url.contains(":memory:") || database_url.contains("mode=memory"))
+}
+
+async fn setup_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
+    Migrator::up(db, None).await?;
+    tracing::info!("Migraciones aplicadas exitosamente");
+
     Ok(())
-}
\ No newline at end of file
+}
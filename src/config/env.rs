@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+/// Lee una variable de entorno no vacía.
+pub fn var_opcional(nombre: &str) -> Option<String> {
+    std::env::var(nombre)
+        .ok()
+        .map(|valor| valor.trim().to_string())
+        .filter(|valor| !valor.is_empty())
+}
+
+/// Lee y convierte una variable de entorno, usando `defecto` si falta o es inválida.
+pub fn var_o<T: FromStr>(nombre: &str, defecto: T) -> T {
+    var_opcional(nombre)
+        .and_then(|valor| valor.parse().ok())
+        .unwrap_or(defecto)
+}
+
+/// Lee una variable booleana aceptando `true/false`, `1/0`, `si/no` y `yes/no`.
+pub fn var_bool(nombre: &str, defecto: bool) -> bool {
+    match var_opcional(nombre).map(|valor| valor.to_lowercase()).as_deref() {
+        Some("true" | "1" | "si" | "sí" | "yes" | "on") => true,
+        Some("false" | "0" | "no" | "off") => false,
+        _ => defecto,
+    }
+}
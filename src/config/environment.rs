@@ -0,0 +1,21 @@
+use crate::config::env::var_opcional;
+
+/// Entorno de ejecución, leído de `APP_ENV` (`development` por defecto).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Development,
+    Production,
+}
+
+impl Environment {
+    pub fn from_env() -> Self {
+        match var_opcional("APP_ENV").map(|valor| valor.to_lowercase()).as_deref() {
+            Some("production" | "produccion" | "producción" | "prod") => Self::Production,
+            _ => Self::Development,
+        }
+    }
+
+    pub fn is_production(self) -> bool {
+        self == Self::Production
+    }
+}
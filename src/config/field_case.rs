@@ -0,0 +1,398 @@
+use serde::ser::{self, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::env::var_opcional;
+
+/// Convención de los nombres de campo en las respuestas desde la v2, leída de
+/// `API_FIELD_CASE` (`snake_case` por defecto o `camelCase`). Los nombres son
+/// siempre los ingleses del modelo (`created_at` o `createdAt`).
+///
+/// Solo se renombran los campos de los structs. Las claves de los mapas son
+/// datos (componentes de `/health/ready`, `changes` de la auditoría...) y se
+/// devuelven tal cual.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldCase {
+    #[default]
+    SnakeCase,
+    CamelCase,
+}
+
+impl FieldCase {
+    pub fn from_env() -> Self {
+        match var_opcional("API_FIELD_CASE").as_deref() {
+            None | Some("snake_case") => Self::SnakeCase,
+            Some("camelCase") => Self::CamelCase,
+            Some(otro) => {
+                tracing::warn!("API_FIELD_CASE no soportado: {}; se usa snake_case", otro);
+                Self::SnakeCase
+            }
+        }
+    }
+
+    /// Serializa `valor` a JSON con los campos de sus structs renombrados.
+    pub fn aplicar<T: Serialize + ?Sized>(self, valor: &T) -> Result<Value, serde_json::Error> {
+        match self {
+            Self::SnakeCase => serde_json::to_value(valor),
+            Self::CamelCase => valor.serialize(Renombrador(self)),
+        }
+    }
+
+    /// `created_at` → `createdAt` en `CamelCase`.
+    pub fn nombre(self, campo: &str) -> String {
+        match self {
+            Self::SnakeCase => campo.to_string(),
+            Self::CamelCase => {
+                let mut partes = campo.split('_').filter(|parte| !parte.is_empty());
+                let primera = partes.next().unwrap_or_default().to_string();
+                partes.fold(primera, |mut nombre, parte| {
+                    let mut letras = parte.chars();
+                    if let Some(inicial) = letras.next() {
+                        nombre.extend(inicial.to_uppercase());
+                        nombre.push_str(letras.as_str());
+                    }
+                    nombre
+                })
+            }
+        }
+    }
+
+    /// Devuelve a los nombres del modelo las claves de un objeto escrito con
+    /// esta convención.
+    pub fn leer(self, valor: Value) -> Value {
+        match (self, valor) {
+            (Self::SnakeCase, valor) => valor,
+            (_, Value::Object(objeto)) => {
+                Value::Object(objeto.into_iter().map(|(clave, valor)| (self.campo(&clave), valor)).collect())
+            }
+            (_, valor) => valor,
+        }
+    }
+
+    /// Inversa de `nombre`: `createdAt` → `created_at`, para leer documentos
+    /// escritos con la convención de las respuestas.
+    pub fn campo(self, nombre: &str) -> String {
+        match self {
+            Self::SnakeCase => nombre.to_string(),
+            Self::CamelCase => nombre.chars().fold(String::new(), |mut campo, letra| {
+                if letra.is_uppercase() {
+                    campo.push('_');
+                    campo.extend(letra.to_lowercase());
+                } else {
+                    campo.push(letra);
+                }
+                campo
+            }),
+        }
+    }
+}
+
+/// Serializador a `Value` que aplica la convención a los campos de los structs
+/// y delega el resto en el de `serde_json`.
+struct Renombrador(FieldCase);
+
+type Error = serde_json::Error;
+
+impl ser::Serializer for Renombrador {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = Secuencia;
+    type SerializeTuple = Secuencia;
+    type SerializeTupleStruct = Secuencia;
+    type SerializeTupleVariant = Secuencia;
+    type SerializeMap = Mapa;
+    type SerializeStruct = Estructura;
+    type SerializeStructVariant = Estructura;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        ser::Serializer::serialize_i128(serde_json::value::Serializer, v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        ser::Serializer::serialize_u128(serde_json::value::Serializer, v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        ser::Serializer::serialize_bytes(serde_json::value::Serializer, v)
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, valor: &T) -> Result<Value, Error> {
+        valor.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _nombre: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(self, _nombre: &'static str, _indice: u32, variante: &'static str) -> Result<Value, Error> {
+        Ok(Value::String(variante.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _nombre: &'static str, valor: &T) -> Result<Value, Error> {
+        valor.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _nombre: &'static str,
+        _indice: u32,
+        variante: &'static str,
+        valor: &T,
+    ) -> Result<Value, Error> {
+        let mut objeto = Map::new();
+        objeto.insert(variante.to_string(), valor.serialize(self)?);
+        Ok(Value::Object(objeto))
+    }
+
+    fn serialize_seq(self, longitud: Option<usize>) -> Result<Secuencia, Error> {
+        Ok(Secuencia { caso: self.0, variante: None, elementos: Vec::with_capacity(longitud.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, longitud: usize) -> Result<Secuencia, Error> {
+        self.serialize_seq(Some(longitud))
+    }
+
+    fn serialize_tuple_struct(self, _nombre: &'static str, longitud: usize) -> Result<Secuencia, Error> {
+        self.serialize_seq(Some(longitud))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _nombre: &'static str,
+        _indice: u32,
+        variante: &'static str,
+        longitud: usize,
+    ) -> Result<Secuencia, Error> {
+        Ok(Secuencia { caso: self.0, variante: Some(variante), elementos: Vec::with_capacity(longitud) })
+    }
+
+    fn serialize_map(self, _longitud: Option<usize>) -> Result<Mapa, Error> {
+        Ok(Mapa { caso: self.0, objeto: Map::new(), clave: None })
+    }
+
+    fn serialize_struct(self, _nombre: &'static str, _longitud: usize) -> Result<Estructura, Error> {
+        Ok(Estructura { caso: self.0, variante: None, objeto: Map::new() })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _nombre: &'static str,
+        _indice: u32,
+        variante: &'static str,
+        _longitud: usize,
+    ) -> Result<Estructura, Error> {
+        Ok(Estructura { caso: self.0, variante: Some(variante), objeto: Map::new() })
+    }
+}
+
+/// `{ "variante": valor }`, como serializa `serde_json` los enums.
+fn con_variante(variante: Option<&'static str>, valor: Value) -> Value {
+    match variante {
+        Some(variante) => Value::Object(Map::from_iter([(variante.to_string(), valor)])),
+        None => valor,
+    }
+}
+
+struct Secuencia {
+    caso: FieldCase,
+    variante: Option<&'static str>,
+    elementos: Vec<Value>,
+}
+
+impl Secuencia {
+    fn agregar<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        self.elementos.push(valor.serialize(Renombrador(self.caso))?);
+        Ok(())
+    }
+
+    fn terminar(self) -> Result<Value, Error> {
+        Ok(con_variante(self.variante, Value::Array(self.elementos)))
+    }
+}
+
+impl ser::SerializeSeq for Secuencia {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        self.agregar(valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.terminar()
+    }
+}
+
+impl ser::SerializeTuple for Secuencia {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        self.agregar(valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.terminar()
+    }
+}
+
+impl ser::SerializeTupleStruct for Secuencia {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        self.agregar(valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.terminar()
+    }
+}
+
+impl ser::SerializeTupleVariant for Secuencia {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        self.agregar(valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.terminar()
+    }
+}
+
+/// Mapa: las claves se conservan; solo los valores pasan por el renombrador.
+struct Mapa {
+    caso: FieldCase,
+    objeto: Map<String, Value>,
+    clave: Option<String>,
+}
+
+impl ser::SerializeMap for Mapa {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, clave: &T) -> Result<(), Error> {
+        // Igual que `serde_json`: claves de texto, números o booleanos
+        let clave = match serde_json::to_value(clave)? {
+            Value::String(clave) => clave,
+            valor @ (Value::Number(_) | Value::Bool(_)) => valor.to_string(),
+            _ => return Err(ser::Error::custom("la clave de un mapa debe ser un texto")),
+        };
+        self.clave = Some(clave);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, valor: &T) -> Result<(), Error> {
+        let clave = self
+            .clave
+            .take()
+            .ok_or_else(|| <Error as ser::Error>::custom("valor de un mapa sin clave"))?;
+        self.objeto.insert(clave, valor.serialize(Renombrador(self.caso))?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.objeto))
+    }
+}
+
+/// Struct: sus campos se renombran según la convención.
+struct Estructura {
+    caso: FieldCase,
+    variante: Option<&'static str>,
+    objeto: Map<String, Value>,
+}
+
+impl Estructura {
+    fn agregar<T: Serialize + ?Sized>(&mut self, campo: &'static str, valor: &T) -> Result<(), Error> {
+        self.objeto.insert(self.caso.nombre(campo), valor.serialize(Renombrador(self.caso))?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Estructura {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, campo: &'static str, valor: &T) -> Result<(), Error> {
+        self.agregar(campo, valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.objeto))
+    }
+}
+
+impl ser::SerializeStructVariant for Estructura {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, campo: &'static str, valor: &T) -> Result<(), Error> {
+        self.agregar(campo, valor)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(con_variante(self.variante, Value::Object(self.objeto)))
+    }
+}
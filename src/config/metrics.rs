@@ -0,0 +1,51 @@
+use crate::config::env::var_opcional;
+
+/// Acceso a `/metrics` en el puerto de la API. Por defecto no se sirve: las
+/// métricas describen el tráfico y la base de datos y no deben quedar a la
+/// vista de cualquiera.
+///
+/// - `METRICS_PORT`: solo en ese puerto de administración, nunca en el de la API.
+/// - `METRICS_TOKEN`: en el puerto de la API con `Authorization: Bearer <token>`.
+/// - `METRICS_PUBLIC=true`: en el puerto de la API sin autenticación.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MetricsAccess {
+    #[default]
+    Deshabilitadas,
+    Token(String),
+    Publicas,
+}
+
+impl MetricsAccess {
+    pub fn from_env() -> Self {
+        if var_opcional("METRICS_PORT").is_some() {
+            return Self::Deshabilitadas;
+        }
+        if let Some(token) = var_opcional("METRICS_TOKEN") {
+            return Self::Token(token);
+        }
+        if var_opcional("METRICS_PUBLIC").is_some_and(|valor| valor == "true") {
+            return Self::Publicas;
+        }
+        Self::Deshabilitadas
+    }
+
+    pub fn en_app(&self) -> bool {
+        *self != Self::Deshabilitadas
+    }
+
+    /// Si la solicitud puede leer las métricas con el `Authorization` recibido.
+    pub fn permite(&self, authorization: Option<&str>) -> bool {
+        match self {
+            Self::Deshabilitadas => false,
+            Self::Publicas => true,
+            Self::Token(token) => authorization
+                .and_then(|valor| valor.strip_prefix("Bearer "))
+                .is_some_and(|recibido| iguales(recibido.as_bytes(), token.as_bytes())),
+        }
+    }
+}
+
+// Comparación en tiempo constante para no revelar el token por la latencia
+fn iguales(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diferencia, (x, y)| diferencia | (x ^ y)) == 0
+}
@@ -1 +1,12 @@
-pub mod database;
\ No newline at end of file
+pub mod api_version;
+pub mod cors;
+pub mod database;
+pub mod env;
+pub mod environment;
+pub mod field_case;
+pub mod metrics;
+pub mod proxies;
+pub mod security_headers;
+pub mod shutdown;
+#[cfg(feature = "tls")]
+pub mod tls;
\ No newline at end of file
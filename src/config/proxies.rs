@@ -0,0 +1,61 @@
+use std::net::IpAddr;
+
+use crate::config::env::var_opcional;
+
+/// Proxies de confianza (`TRUSTED_PROXIES`, IPs separadas por comas). Solo
+/// cuando la conexión llega de uno de ellos se lee `X-Forwarded-For`; si no,
+/// la cabecera la controla el cliente y se ignora.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    ips: Vec<IpAddr>,
+}
+
+impl TrustedProxies {
+    pub fn new(ips: Vec<IpAddr>) -> Self {
+        Self { ips }
+    }
+
+    pub fn from_env() -> Self {
+        let ips = var_opcional("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .filter_map(|ip| match ip.parse() {
+                Ok(ip) => Some(ip),
+                Err(_) => {
+                    tracing::warn!("⚠️ TRUSTED_PROXIES: se ignora la IP inválida '{}'", ip);
+                    None
+                }
+            })
+            .collect();
+
+        Self { ips }
+    }
+
+    pub fn es_de_confianza(&self, ip: &IpAddr) -> bool {
+        self.ips.contains(ip)
+    }
+
+    /// IP del cliente: la de la conexión o, si llega por proxies de confianza,
+    /// la última de `X-Forwarded-For` que no sea de uno de ellos. Cada proxy
+    /// añade la suya al final, así que las de la izquierda las pudo inventar
+    /// el cliente.
+    pub fn ip_cliente(&self, conexion: IpAddr, x_forwarded_for: Option<&str>) -> IpAddr {
+        if !self.es_de_confianza(&conexion) {
+            return conexion;
+        }
+
+        let mut ip = conexion;
+        for salto in x_forwarded_for.unwrap_or_default().rsplit(',') {
+            match salto.trim().parse::<IpAddr>() {
+                Ok(anterior) => ip = anterior,
+                Err(_) => break,
+            }
+            if !self.es_de_confianza(&ip) {
+                break;
+            }
+        }
+        ip
+    }
+}
@@ -0,0 +1,131 @@
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+
+use crate::config::env::{var_bool, var_o, var_opcional};
+use crate::config::api_version::sin_version;
+use crate::config::environment::Environment;
+
+// Valor que desactiva una cabecera configurable
+const DESACTIVADA: &str = "off";
+
+// Swagger UI se sirve desde la propia API; sus componentes usan estilos en línea
+const CSP_DOCUMENTACION: &str = "default-src 'none'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
+     img-src 'self' data:; connect-src 'self'; frame-ancestors 'none'";
+
+/// Cabeceras de seguridad que se añaden a todas las respuestas, leídas de
+/// variables de entorno `SECURITY_*`, con ajustes por prefijo de ruta.
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersSettings {
+    pub cabeceras: Vec<(HeaderName, HeaderValue)>,
+    pub rutas: Vec<RouteOverride>,
+}
+
+/// Cambios en las cabeceras para las rutas que empiezan por `prefijo`:
+/// `Some` reemplaza o añade la cabecera, `None` la quita.
+#[derive(Debug, Clone)]
+pub struct RouteOverride {
+    pub prefijo: String,
+    pub cabeceras: Vec<(HeaderName, Option<HeaderValue>)>,
+}
+
+impl SecurityHeadersSettings {
+    pub fn from_env() -> Self {
+        let produccion = Environment::from_env().is_production();
+        let mut cabeceras = vec![(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        )];
+
+        // HSTS solo tiene sentido detrás de HTTPS: activo por defecto en producción
+        if var_bool("SECURITY_HSTS", produccion) {
+            let mut hsts = format!("max-age={}", var_o::<u64>("SECURITY_HSTS_MAX_AGE", 31_536_000));
+            if var_bool("SECURITY_HSTS_INCLUDE_SUBDOMAINS", true) {
+                hsts.push_str("; includeSubDomains");
+            }
+            if var_bool("SECURITY_HSTS_PRELOAD", false) {
+                hsts.push_str("; preload");
+            }
+            agregar(&mut cabeceras, header::STRICT_TRANSPORT_SECURITY, &hsts);
+        }
+
+        let configurables = [
+            (header::X_FRAME_OPTIONS, "SECURITY_FRAME_OPTIONS", "DENY"),
+            (header::REFERRER_POLICY, "SECURITY_REFERRER_POLICY", "no-referrer"),
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "SECURITY_CSP",
+                "default-src 'none'; frame-ancestors 'none'",
+            ),
+            (
+                HeaderName::from_static("permissions-policy"),
+                "SECURITY_PERMISSIONS_POLICY",
+                "camera=(), microphone=(), geolocation=()",
+            ),
+        ];
+
+        for (nombre, variable, defecto) in configurables {
+            let valor = var_opcional(variable).unwrap_or_else(|| defecto.to_string());
+            if !valor.eq_ignore_ascii_case(DESACTIVADA) {
+                agregar(&mut cabeceras, nombre, &valor);
+            }
+        }
+
+        Self { cabeceras, rutas: Vec::new() }
+            // Ni el navegador ni los proxies deben guardar tokens ni credenciales
+            .ruta("/api/auth", header::CACHE_CONTROL, Some("no-store"))
+            // Swagger UI necesita JavaScript y estilos, solo del propio origen
+            .ruta("/api/docs", header::CONTENT_SECURITY_POLICY, Some(CSP_DOCUMENTACION))
+    }
+
+    /// Añade un ajuste para las rutas que empiezan por `prefijo`; `None` quita la cabecera.
+    pub fn ruta(mut self, prefijo: &str, nombre: HeaderName, valor: Option<&str>) -> Self {
+        let valor = match valor.map(HeaderValue::from_str) {
+            Some(Ok(valor)) => Some(valor),
+            Some(Err(_)) => {
+                tracing::warn!("Valor inválido para la cabecera {} en {}", nombre, prefijo);
+                return self;
+            }
+            None => None,
+        };
+
+        match self.rutas.iter_mut().find(|ruta| ruta.prefijo == prefijo) {
+            Some(ruta) => ruta.cabeceras.push((nombre, valor)),
+            None => self.rutas.push(RouteOverride {
+                prefijo: prefijo.to_string(),
+                cabeceras: vec![(nombre, valor)],
+            }),
+        }
+        self
+    }
+
+    /// Cabeceras que corresponden a `path`, con los ajustes de ruta aplicados en orden.
+    pub fn para_ruta(&self, path: &str) -> Vec<(HeaderName, HeaderValue)> {
+        let mut cabeceras = self.cabeceras.clone();
+        // Los prefijos se escriben sin versión: `/api/auth` cubre también `/api/v2/auth`
+        let path = sin_version(path);
+
+        for ruta in self.rutas.iter().filter(|ruta| ruta.aplica(&path)) {
+            for (nombre, valor) in &ruta.cabeceras {
+                cabeceras.retain(|(existente, _)| existente != nombre);
+                if let Some(valor) = valor {
+                    cabeceras.push((nombre.clone(), valor.clone()));
+                }
+            }
+        }
+
+        cabeceras
+    }
+}
+
+impl RouteOverride {
+    fn aplica(&self, path: &str) -> bool {
+        path.strip_prefix(self.prefijo.as_str())
+            .is_some_and(|resto| resto.is_empty() || resto.starts_with('/'))
+    }
+}
+
+fn agregar(cabeceras: &mut Vec<(HeaderName, HeaderValue)>, nombre: HeaderName, valor: &str) {
+    match HeaderValue::from_str(valor) {
+        Ok(valor) => cabeceras.push((nombre, valor)),
+        Err(_) => tracing::warn!("Valor inválido para la cabecera {}: {}", nombre, valor),
+    }
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use crate::config::env::var_o;
+use crate::config::environment::Environment;
+
+/// Ajustes del apagado ordenado, leídos de variables de entorno `SHUTDOWN_*`.
+#[derive(Debug, Clone)]
+pub struct ShutdownSettings {
+    /// Tiempo máximo para que terminen las solicitudes en curso.
+    pub timeout: Duration,
+    /// Espera entre marcar la sonda de readiness como fallida y dejar de
+    /// aceptar conexiones, para que el balanceador retire la instancia.
+    pub retraso_readiness: Duration,
+}
+
+impl ShutdownSettings {
+    pub fn from_env() -> Self {
+        let segundos = |nombre, defecto| Duration::from_secs(var_o(nombre, defecto));
+        // En desarrollo no hay balanceador que esperar
+        let retraso_defecto = if Environment::from_env().is_production() { 5 } else { 0 };
+
+        Self {
+            timeout: segundos("SHUTDOWN_TIMEOUT_SECS", 30),
+            retraso_readiness: segundos("SHUTDOWN_READINESS_DELAY_SECS", retraso_defecto),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::env::var_opcional;
+
+/// Ajustes de HTTPS, leídos de variables de entorno `TLS_*`. Se activa al
+/// configurar el certificado y la clave; solo uno de los dos es un error.
+#[derive(Debug, Clone)]
+pub struct TlsSettings {
+    pub certificado: PathBuf,
+    pub clave: PathBuf,
+    /// CA con la que se verifican los certificados de cliente (mTLS).
+    pub ca_clientes: Option<PathBuf>,
+    /// Con `TLS_CLIENT_AUTH=required` se rechazan los clientes sin certificado.
+    pub cliente_obligatorio: bool,
+    /// Puerto HTTP que redirige a HTTPS.
+    pub puerto_redireccion: Option<u16>,
+    /// Cada cuánto se comprueba si el certificado o la clave cambiaron.
+    pub intervalo_recarga: Duration,
+}
+
+impl TlsSettings {
+    /// `Ok(None)` sin TLS; `Err` con una configuración que no se puede usar,
+    /// para que el servidor no arranque en HTTP sin avisar.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        Self::desde(var_opcional)
+    }
+
+    /// Como `from_env`, con las variables leídas por `variable`.
+    pub fn desde(variable: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, String> {
+        let (certificado, clave) = match (variable("TLS_CERT_PATH"), variable("TLS_KEY_PATH")) {
+            (None, None) => return Ok(None),
+            (Some(certificado), Some(clave)) => (certificado, clave),
+            (Some(_), None) => return Err("TLS_CERT_PATH está configurada pero falta TLS_KEY_PATH".to_string()),
+            (None, Some(_)) => return Err("TLS_KEY_PATH está configurada pero falta TLS_CERT_PATH".to_string()),
+        };
+
+        let puerto_redireccion = variable("TLS_REDIRECT_PORT")
+            .map(|valor| {
+                valor
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|puerto| *puerto > 0)
+                    .ok_or_else(|| format!("TLS_REDIRECT_PORT debe ser un puerto válido: {}", valor))
+            })
+            .transpose()?;
+        let intervalo_recarga = match variable("TLS_RELOAD_INTERVAL_SECS") {
+            None => 30,
+            Some(valor) => valor
+                .parse::<u64>()
+                .ok()
+                .filter(|segundos| *segundos > 0)
+                .ok_or_else(|| format!("TLS_RELOAD_INTERVAL_SECS debe ser un número de segundos mayor que 0: {}", valor))?,
+        };
+
+        Ok(Some(Self {
+            certificado: PathBuf::from(certificado),
+            clave: PathBuf::from(clave),
+            ca_clientes: variable("TLS_CLIENT_CA_PATH").map(PathBuf::from),
+            cliente_obligatorio: variable("TLS_CLIENT_AUTH")
+                .is_some_and(|modo| modo.eq_ignore_ascii_case("required")),
+            puerto_redireccion,
+            intervalo_recarga: Duration::from_secs(intervalo_recarga),
+        }))
+    }
+}
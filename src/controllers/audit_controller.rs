@@ -0,0 +1,87 @@
+use actix_web::web;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use crate::config::database::ReadReplica;
+use crate::models::audit_log::{AuditQuery, Column, Entity as AuditLogEntity, Model as AuditLog};
+use crate::responses::api_response::{ApiResponse, Paginacion};
+use crate::errors::api_error::{ApiError, ErrorResponse};
+use crate::utils::jwt::Claims;
+use crate::services::UserService;
+
+const POR_PAGINA_DEFECTO: u64 = 20;
+const POR_PAGINA_MAXIMO: u64 = 100;
+
+/// Consulta el registro de auditoría (administrador)
+#[utoipa::path(get, path = "/api/auditoria", tag = "auditoría", security(("bearer" = [])),
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Página de eventos, del más reciente al más antiguo", body = Object),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 403, description = "Se requieren permisos de administrador", body = ErrorResponse),
+    ),
+)]
+pub async fn get_audit_logs(
+    db: web::Data<ReadReplica>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    filtros: web::Query<AuditQuery>,
+) -> Result<ApiResponse<Vec<AuditLog>>, ApiError> {
+    usuarios.requerir_admin(&claims).await?;
+
+    let pagina = filtros.pagina.unwrap_or(1).max(1);
+    let por_pagina = filtros
+        .por_pagina
+        .unwrap_or(POR_PAGINA_DEFECTO)
+        .clamp(1, POR_PAGINA_MAXIMO);
+
+    let mut consulta = AuditLogEntity::find();
+
+    if let Some(accion) = &filtros.accion {
+        consulta = consulta.filter(Column::Action.eq(accion.as_str()));
+    }
+    if let Some(actor_id) = filtros.actor_id {
+        consulta = consulta.filter(Column::ActorId.eq(actor_id));
+    }
+    if let Some(objetivo_id) = filtros.objetivo_id {
+        consulta = consulta.filter(Column::TargetId.eq(objetivo_id));
+    }
+    if let Some(desde) = filtros.desde {
+        consulta = consulta.filter(Column::CreatedAt.gte(desde));
+    }
+    if let Some(hasta) = filtros.hasta {
+        consulta = consulta.filter(Column::CreatedAt.lte(hasta));
+    }
+
+    let paginador = consulta
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .paginate(db.conexion(), por_pagina);
+
+    let totales = paginador
+        .num_items_and_pages()
+        .await
+        .map_err(ApiError::interno)?;
+
+    let registros = paginador
+        .fetch_page(pagina - 1)
+        .await
+        .map_err(ApiError::interno)?;
+
+    let paginacion = Paginacion {
+        pagina,
+        por_pagina,
+        total: totales.number_of_items,
+        total_paginas: totales.number_of_pages,
+    };
+    let legado = serde_json::json!({
+        "exito": true,
+        "datos": registros,
+        "paginacion": {
+            "pagina": pagina,
+            "por_pagina": por_pagina,
+            "total": totales.number_of_items,
+            "total_paginas": totales.number_of_pages
+        }
+    });
+
+    Ok(ApiResponse::new(registros).paginacion(paginacion).legado(legado))
+}
@@ -1,72 +1,129 @@
-use actix_web::{web, HttpResponse};
-use sea_orm::{DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter};
-use crate::models::user::{LoginDto, Entity as UserEntity};
-use crate::utils::hash::verify_password;
+use actix_web::{web, HttpRequest};
+use sea_orm::DatabaseConnection;
+use crate::models::audit_log;
+use crate::models::user::LoginDto;
+use crate::services::{Autenticacion, UserService};
+use crate::utils::audit::{self, AuditEvent};
 use crate::utils::jwt::{generar_token};
-use crate::errors::api_error::ApiError;
+use crate::utils::metrics::METRICAS;
+use crate::errors::api_error::{ApiError, ErrorResponse};
+use crate::i18n::{Clave, Mensaje};
+use crate::responses::api_response::ApiResponse;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
-struct LoginResponse {
+/// Cuerpo de `login` en la v1.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginResponse {
     success: bool,
-    message: String,
+    #[schema(value_type = String)]
+    message: Mensaje,
     token: String,
+    usuario: UsuarioLegado,
+}
+
+/// `UserInfo` con los nombres de campo de la v1.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UsuarioLegado {
+    id: i32,
+    email: String,
+    nombre: String,
+}
+
+/// Datos de `login` desde la v2; `meta.message` lleva el mensaje.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Sesion {
+    token: String,
+    #[serde(rename = "user")]
     usuario: UserInfo,
 }
 
-#[derive(Debug, Serialize)]
-struct UserInfo {
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserInfo {
     id: i32, // Cambiado de String a i32
     email: String,
+    #[serde(rename = "name")]
     nombre: String,
 }
 
+/// Inicia sesión y devuelve un token JWT
+#[utoipa::path(post, path = "/api/auth/login", tag = "auth", request_body = LoginDto, responses(
+    (status = 200, description = "Credenciales correctas", body = LoginResponse),
+    (status = 400, description = "Faltan el email o la contraseña", body = ErrorResponse),
+    (status = 401, description = "Credenciales inválidas", body = ErrorResponse),
+    (status = 403, description = "La cuenta está deshabilitada", body = ErrorResponse),
+))]
 pub async fn login(
+    req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
     login_data: web::Json<LoginDto>,
-) -> Result<HttpResponse, ApiError> {
+) -> Result<ApiResponse<Sesion>, ApiError> {
     // Validar que el email y contraseña no estén vacíos
     if login_data.email.trim().is_empty() || login_data.password.trim().is_empty() {
-        return Err(ApiError::bad_request(
-            "El email y la contraseña son requeridos".to_string()
-        ));
+        return Err(ApiError::bad_request(Clave::CredencialesRequeridas));
     }
 
-    let usuario = UserEntity::find()
-        .filter(crate::models::user::Column::Email.eq(&login_data.email))
-        .one(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
+    let usuario = match usuarios.autenticar(&login_data.email, &login_data.password).await? {
+        Autenticacion::Valida(usuario) => usuario,
+        Autenticacion::Deshabilitada(usuario) => {
+            registrar_login_fallido(&db, &req, Some(usuario.id), &login_data.email).await;
+            return Err(ApiError::forbidden(Clave::CuentaDeshabilitada));
+        }
+        Autenticacion::Invalida(usuario_id) => {
+            registrar_login_fallido(&db, &req, usuario_id, &login_data.email).await;
+            return Err(ApiError::unauthorized(Clave::CredencialesInvalidas));
+        }
+    };
 
-    match usuario {
-        Some(usuario) => {
-            // Verificar la contraseña
-            if verify_password(&login_data.password, &usuario.password)
-                .map_err(|e| ApiError::internal_server_error(e.to_string()))? {
-                
-                // Generar token JWT (convertir id a String para el token)
-                let token = generar_token(usuario.id.to_string())
-                    .map_err(|e| ApiError::internal_server_error(
-                        format!("Error al generar el token: {}", e)
-                    ))?;
+    // Generar token JWT (convertir id a String para el token)
+    let token = generar_token(usuario.id.to_string())
+        .map_err(|e| ApiError::interno(format!("Error al generar el token: {}", e)))?;
 
-                // Crear respuesta
-                let respuesta = LoginResponse {
-                    success: true,
-                    message: "Inicio de sesión exitoso".to_string(),
-                    token,
-                    usuario: UserInfo {
-                        id: usuario.id, // Ahora es i32 directamente
-                        email: usuario.email,
-                        nombre: usuario.name,
-                    },
-                };
+    METRICAS.logins.with_label_values(&["success"]).inc();
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::LOGIN_SUCCESS).actor(usuario.id).target(usuario.id),
+    ).await;
 
-                Ok(HttpResponse::Ok().json(respuesta))
-            } else {
-                Err(ApiError::unauthorized("Credenciales inválidas".to_string()))
-            }
-        }
-        None => Err(ApiError::unauthorized("Credenciales inválidas".to_string())),
-    }
+    // Crear respuesta
+    let sesion = Sesion {
+        token,
+        usuario: UserInfo {
+            id: usuario.id, // Ahora es i32 directamente
+            email: usuario.email,
+            nombre: usuario.name,
+        },
+    };
+    let legado = LoginResponse {
+        success: true,
+        message: Clave::InicioSesionExitoso.into(),
+        token: sesion.token.clone(),
+        usuario: UsuarioLegado {
+            id: sesion.usuario.id,
+            email: sesion.usuario.email.clone(),
+            nombre: sesion.usuario.nombre.clone(),
+        },
+    };
+
+    Ok(ApiResponse::new(sesion)
+        .mensaje(Clave::InicioSesionExitoso)
+        .legado(serde_json::to_value(legado).map_err(ApiError::interno)?))
+}
+
+async fn registrar_login_fallido(
+    db: &DatabaseConnection,
+    req: &HttpRequest,
+    usuario_id: Option<i32>,
+    email: &str,
+) {
+    METRICAS.logins.with_label_values(&["failure"]).inc();
+    audit::registrar(
+        db,
+        req,
+        AuditEvent::new(audit_log::LOGIN_FAILURE)
+            .target(usuario_id)
+            .cambios(serde_json::json!({ "email": email })),
+    ).await;
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::sync::{Arc, LazyLock};
+
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::{web, HttpResponse};
+use utoipa_swagger_ui::Config;
+
+use crate::openapi::DOCUMENTO;
+
+// Los archivos de Swagger UI van incluidos en el binario (`utoipa-swagger-ui`,
+// versión vendored): la página no depende de ningún CDN
+static CONFIG_SWAGGER: LazyLock<Arc<Config<'static>>> =
+    LazyLock::new(|| Arc::new(Config::from("/api/openapi.json")));
+
+// Archivos que usa la página de `/api/docs`
+const ARCHIVOS_SWAGGER: [&str; 2] = ["swagger-ui.css", "swagger-ui-bundle.js"];
+
+/// Documento OpenAPI 3.1 de la API
+#[utoipa::path(get, path = "/api/openapi.json", tag = "documentación", responses(
+    (status = 200, description = "Documento OpenAPI", body = Object),
+))]
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(&*DOCUMENTO)
+}
+
+/// Visor Swagger UI del documento OpenAPI
+#[utoipa::path(get, path = "/api/docs", tag = "documentación", responses(
+    (status = 200, description = "Página HTML", body = String, content_type = "text/html"),
+))]
+pub async fn swagger_ui() -> HttpResponse {
+    let pagina = r#"<!DOCTYPE html>
+<html lang="es">
+<head>
+  <meta charset="utf-8">
+  <title>API Rust con Actix-web - Documentación</title>
+  <link rel="stylesheet" href="/api/docs/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="/api/docs/swagger-ui-bundle.js"></script>
+  <script src="/api/docs/swagger-initializer.js"></script>
+</body>
+</html>
+"#;
+
+    HttpResponse::Ok().content_type("text/html; charset=utf-8").body(pagina)
+}
+
+/// Archivos estáticos de Swagger UI
+#[utoipa::path(get, path = "/api/docs/{archivo}", tag = "documentación",
+    params(("archivo" = String, Path, description = "`swagger-ui.css` o `swagger-ui-bundle.js`")),
+    responses(
+        (status = 200, description = "CSS o JavaScript", body = String),
+        (status = 404, description = "Archivo desconocido"),
+    ),
+)]
+pub async fn swagger_archivo(archivo: web::Path<String>) -> HttpResponse {
+    if !ARCHIVOS_SWAGGER.contains(&archivo.as_str()) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match utoipa_swagger_ui::serve(&archivo, CONFIG_SWAGGER.clone()) {
+        Ok(Some(contenido)) => HttpResponse::Ok()
+            .content_type(contenido.content_type)
+            // Cambian solo con una versión nueva del binario
+            .insert_header(CacheControl(vec![CacheDirective::Public, CacheDirective::MaxAge(86400)]))
+            .body(contenido.bytes.into_owned()),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("No se pudo servir {} de Swagger UI: {}", archivo, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Script que inicializa Swagger UI
+#[utoipa::path(get, path = "/api/docs/swagger-initializer.js", tag = "documentación", responses(
+    (status = 200, description = "JavaScript", body = String, content_type = "text/javascript"),
+))]
+pub async fn swagger_initializer() -> HttpResponse {
+    // Archivo aparte en lugar de un script en línea: la CSP no permite `unsafe-inline`
+    HttpResponse::Ok()
+        .content_type("text/javascript; charset=utf-8")
+        .body(r##"window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });"##)
+}
@@ -1,28 +1,144 @@
-use actix_web::HttpResponse;
+use actix_web::http::StatusCode;
+use actix_web::web;
+use sea_orm::DatabaseConnection;
+use sea_orm_migration::MigratorTrait;
+use serde_json::{json, Map, Value};
+use std::time::Instant;
 
-pub async fn health_check() -> HttpResponse {
-    HttpResponse::Ok().json(serde_json::json!({
+use crate::config::database::{estadisticas_pool, ReadReplica};
+use crate::i18n::{Clave, Mensaje};
+use crate::migrations::Migrator;
+use crate::responses::api_response::ApiResponse;
+use crate::openapi::{operaciones, DOCUMENTO};
+use crate::utils::lifecycle::Lifecycle;
+
+/// Estado del servidor
+#[utoipa::path(get, path = "/api/salud", tag = "salud", responses(
+    (status = 200, description = "El servidor responde", body = Object),
+))]
+pub async fn health_check() -> ApiResponse<Value> {
+    let legado = json!({
         "estado": "OK",
-        "mensaje": "Servidor funcionando correctamente"
-    }))
+        "mensaje": Mensaje::new(Clave::ServidorFuncionando)
+    });
+
+    ApiResponse::new(json!({ "estado": "OK" }))
+        .mensaje(Clave::ServidorFuncionando)
+        .legado(legado)
+}
+
+// Liveness: el proceso responde; no consulta dependencias para que un fallo
+// de la base de datos no provoque reinicios del contenedor
+/// Sonda de liveness
+#[utoipa::path(get, path = "/api/salud/vivo", tag = "salud", responses(
+    (status = 200, description = "El proceso está vivo", body = Object),
+))]
+pub async fn liveness() -> ApiResponse<Value> {
+    ApiResponse::new(json!({ "estado": "vivo" }))
+}
+
+// Readiness: puede recibir tráfico solo si la base de datos responde, no hay
+// migraciones pendientes y el servidor no se está apagando. La sonda es
+// pública: cada componente devuelve solo su estado y su latencia, y el detalle
+// de los fallos (errores de la base de datos, migraciones pendientes) va al log
+/// Sonda de readiness con estado de dependencias
+#[utoipa::path(get, path = "/api/salud/listo", tag = "salud", responses(
+    (status = 200, description = "Listo para recibir tráfico", body = Object),
+    (status = 503, description = "Alguna dependencia falla o el servidor se está apagando", body = Object),
+))]
+pub async fn readiness(
+    db: web::Data<DatabaseConnection>,
+    replica: web::Data<ReadReplica>,
+    lifecycle: web::Data<Lifecycle>,
+) -> ApiResponse<Value> {
+    let apagando = lifecycle.esta_apagando();
+    let (base_de_datos, db_ok) = comprobar_conexion("base de datos", db.get_ref()).await;
+    let (replica, replica_ok) = comprobar_conexion("réplica", replica.conexion()).await;
+    let (migraciones, migraciones_ok) = comprobar_migraciones(db.get_ref()).await;
+
+    let pool = match estadisticas_pool(db.get_ref()) {
+        Some(stats) if stats.en_uso >= stats.maximo => {
+            tracing::warn!("Pool de conexiones saturado: {} en uso de {}", stats.en_uso, stats.maximo);
+            json!({ "estado": "saturado" })
+        }
+        Some(_) => json!({ "estado": "ok" }),
+        None => json!({ "estado": "desconocido" }),
+    };
+
+    let listo = !apagando && db_ok && replica_ok && migraciones_ok;
+    let cuerpo = json!({
+        "estado": if listo { "listo" } else { "no_listo" },
+        "apagando": apagando,
+        "componentes": {
+            "base_de_datos": base_de_datos,
+            "replica": replica,
+            "migraciones": migraciones,
+            "pool": pool
+        }
+    });
+
+    let estado = if listo { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    ApiResponse::new(cuerpo).estado(estado)
 }
 
-pub async fn api_info() -> HttpResponse {
-    HttpResponse::Ok().json(serde_json::json!({
+async fn comprobar_conexion(componente: &str, db: &DatabaseConnection) -> (Value, bool) {
+    let inicio = Instant::now();
+    let resultado = db.ping().await;
+    let latencia_ms = inicio.elapsed().as_secs_f64() * 1000.0;
+
+    match resultado {
+        Ok(()) => (json!({ "estado": "ok", "latencia_ms": latencia_ms }), true),
+        Err(e) => {
+            tracing::error!("Readiness: la {} no responde: {}", componente, e);
+            (json!({ "estado": "error", "latencia_ms": latencia_ms }), false)
+        }
+    }
+}
+
+async fn comprobar_migraciones(db: &DatabaseConnection) -> (Value, bool) {
+    let inicio = Instant::now();
+    let resultado = Migrator::get_pending_migrations(db).await;
+    let latencia_ms = inicio.elapsed().as_secs_f64() * 1000.0;
+
+    match resultado {
+        Ok(pendientes) if pendientes.is_empty() => (json!({ "estado": "ok", "latencia_ms": latencia_ms }), true),
+        Ok(pendientes) => {
+            let nombres: Vec<_> = pendientes.iter().map(|m| m.name().to_string()).collect();
+            tracing::warn!("Readiness: {} migraciones pendientes: {}", nombres.len(), nombres.join(", "));
+            (json!({ "estado": "pendientes", "latencia_ms": latencia_ms }), false)
+        }
+        Err(e) => {
+            tracing::error!("Readiness: no se pudieron consultar las migraciones: {}", e);
+            (json!({ "estado": "error", "latencia_ms": latencia_ms }), false)
+        }
+    }
+}
+
+/// Información de la API
+#[utoipa::path(get, path = "/api/info", tag = "salud", responses(
+    (status = 200, description = "Nombre, versión y endpoints disponibles", body = Object),
+))]
+pub async fn api_info() -> ApiResponse<Value> {
+    // Los endpoints salen del documento OpenAPI para que no se desactualicen
+    let endpoints: Map<String, Value> = DOCUMENTO
+        .paths
+        .paths
+        .iter()
+        .flat_map(|(ruta, item)| {
+            operaciones(item).map(move |(metodo, operacion)| {
+                let resumen = operacion.summary.clone().unwrap_or_default();
+                (format!("{} {}", metodo, ruta), Value::String(resumen))
+            })
+        })
+        .collect();
+
+    ApiResponse::new(json!({
         "nombre": "API Rust con Actix-web",
-        "version": "0.1.0",
+        "version": env!("CARGO_PKG_VERSION"),
         "descripcion": "Una API REST simple construida con Rust y el framework Actix-web",
         "framework": "Actix-web",
         "arquitectura": "MVC",
-        "endpoints": {
-            "GET /api/salud": "Verificación del estado del servidor",
-            "GET /api/info": "Información de la API",
-            "POST /api/auth/registro": "Registrar nuevo usuario",
-            "POST /api/auth/login": "Iniciar sesión",
-            "GET /api/usuarios": "Obtener todos los usuarios (protegido)",
-            "GET /api/usuarios/{id}": "Obtener usuario por ID (protegido)",
-            "PUT /api/usuarios/{id}": "Actualizar usuario (protegido)",
-            "DELETE /api/usuarios/{id}": "Eliminar usuario (protegido)"
-        }
+        "documentacion": "/api/docs",
+        "endpoints": endpoints
     }))
-}
\ No newline at end of file
+}
@@ -0,0 +1,29 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::DatabaseConnection;
+use crate::config::database::ReadReplica;
+use crate::errors::api_error::{ApiError, ErrorResponse};
+use crate::utils::metrics::{exportar, registrar_pool};
+
+/// Métricas en formato de texto de Prometheus
+///
+/// En el puerto de la API solo con `METRICS_PUBLIC=true` o, con `METRICS_TOKEN`,
+/// enviando ese token como `Bearer`.
+#[utoipa::path(get, path = "/metrics", tag = "métricas", responses(
+    (status = 200, description = "Métricas", body = String, content_type = "text/plain"),
+    (status = 401, description = "Falta `METRICS_TOKEN` o no coincide", body = ErrorResponse),
+))]
+pub async fn metrics(
+    db: web::Data<DatabaseConnection>,
+    replica: web::Data<ReadReplica>,
+) -> Result<HttpResponse, ApiError> {
+    // El estado del pool se toma en el momento del scrape
+    registrar_pool("primary", db.get_ref());
+    registrar_pool("replica", replica.conexion());
+
+    let cuerpo = exportar()
+        .map_err(ApiError::interno)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(cuerpo))
+}
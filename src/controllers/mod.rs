@@ -1,3 +1,5 @@
 pub mod user_controller;
 pub  mod  health_controller;
-pub mod  auth_controller;
\ No newline at end of file
+pub mod  auth_controller;
+pub mod audit_controller;
+pub mod metrics_controller;pub mod docs_controller;
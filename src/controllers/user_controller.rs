@@ -1,147 +1,488 @@
-use actix_web::{web, HttpResponse, Result};
-use sea_orm::{DatabaseConnection, EntityTrait, ActiveModelTrait, Set, ModelTrait, IntoActiveModel};
-use crate::models::user::{CreateUserDto, UpdateUserDto, Entity as UserEntity, Model as UserModel};
-use crate::utils::hash::hash_password;
-use crate::errors::api_error::ApiError;
+use actix_web::{web, Either, HttpRequest, HttpResponse, Result};
+use actix_web::http::header::ETag;
+use actix_web::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use crate::models::audit_log;
+use crate::models::user::{
+    ChangePasswordDto, ConfirmEmailDto, CreateUserDto, DeleteAccountDto, Model as User, UpdateProfileDto,
+    UpdateUserDto, ROLE_USER,
+};
+use crate::services::UserService;
+use crate::utils::audit::{self, diff_usuario, AuditEvent};
+use crate::utils::etag::{etag_usuario, no_modificado, verificar_if_match};
+use crate::utils::patch;
+use crate::errors::api_error::{ApiError, ErrorResponse};
+use crate::i18n::{Clave, Mensaje};
+use crate::middleware::api_version::FormatoRespuesta;
+use crate::responses::api_response::ApiResponse;
 use crate::utils::jwt::Claims;
-use chrono::Utc;
+use serde::Serialize;
+use utoipa::ToSchema;
 
+/// Usuario visto por sí mismo: incluye el email pendiente de confirmar, que
+/// no aparece en `/api/usuarios`. Repite los campos de `User` en lugar de usar
+/// `#[serde(flatten)]`, que los serializa como un mapa y los dejaría fuera de
+/// la convención de `API_FIELD_CASE`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Perfil {
+    id: i32,
+    name: String,
+    email: String,
+    role: String,
+    #[schema(value_type = String, format = DateTime)]
+    created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    updated_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    deleted_at: Option<DateTime<Utc>>,
+    version: i32,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    disabled_at: Option<DateTime<Utc>>,
+    locale: Option<String>,
+    pending_email: Option<String>,
+}
+
+impl From<User> for Perfil {
+    fn from(usuario: User) -> Self {
+        Self {
+            id: usuario.id,
+            name: usuario.name,
+            email: usuario.email,
+            role: usuario.role,
+            created_at: usuario.created_at,
+            updated_at: usuario.updated_at,
+            deleted_at: usuario.deleted_at,
+            version: usuario.version,
+            disabled_at: usuario.disabled_at,
+            locale: usuario.locale,
+            pending_email: usuario.pending_email,
+        }
+    }
+}
 
+/// Perfil del usuario autenticado
+#[utoipa::path(get, path = "/api/perfil", tag = "perfil", security(("bearer" = [])), responses(
+    (status = 200, description = "Perfil del usuario; desde la v2, `data` es `Perfil`", body = Object),
+    (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+    (status = 404, description = "El usuario ya no existe", body = ErrorResponse),
+))]
 pub async fn perfil(
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+) -> Result<ApiResponse<Perfil>, ApiError> {
+    let usuario_id = claims
+        .usuario_id()
+        .ok_or_else(|| ApiError::bad_request(Clave::IdUsuarioInvalido))?;
+    let usuario = usuarios.obtener(usuario_id).await?;
+
+    let legado = serde_json::json!({
+        "exito": true,
+        "datos": {
+            "id": usuario.id,
+            "nombre": usuario.name,
+            "email": usuario.email,
+            "fecha_creacion": usuario.created_at
+        },
+        "mensaje": Mensaje::new(Clave::PerfilObtenido)
+    });
+
+    Ok(ApiResponse::new(Perfil::from(usuario)).mensaje(Clave::PerfilObtenido).legado(legado))
+}
+
+/// Modifica el nombre, el email o el idioma del usuario autenticado
+#[utoipa::path(patch, path = "/api/perfil", tag = "perfil", security(("bearer" = [])),
+    request_body = UpdateProfileDto,
+    responses(
+        (status = 200, description = "Perfil actualizado; un email nuevo queda en `pending_email` hasta confirmarlo", body = Perfil),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 409, description = "El email ya está en uso", body = ErrorResponse),
+        (status = 422, description = "Error de validación", body = ErrorResponse),
+        (status = 503, description = "No se pudo enviar el código de confirmación", body = ErrorResponse),
+    ),
+)]
+pub async fn update_profile(
+    req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
     claims: web::ReqData<Claims>,
-) -> Result<HttpResponse, ApiError> {
-    let usuario_id = &claims.sub;
- println!("{}",&usuario_id.to_string());
-    let usuario = UserEntity::find_by_id(usuario_id.parse::<i32>()
-        .map_err(|_| ApiError::bad_request("ID de usuario inválido".to_string()))?)
-        .one(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-
-    match usuario {
-        Some(usuario) => {
-            Ok(HttpResponse::Ok().json(serde_json::json!({
-                "exito": true,
-                "datos": {
-                    "id": usuario.id,
-                    "nombre": usuario.name,
-                    "email": usuario.email,
-                    "fecha_creacion": usuario.created_at
-                },
-                "mensaje": "Perfil obtenido exitosamente"
-            })))
-        }
-        None => Err(ApiError::not_found("Usuario no encontrado".to_string())),
+    datos: web::Json<UpdateProfileDto>,
+) -> Result<ApiResponse<Perfil>, ApiError> {
+    let antes = usuario_autenticado(&usuarios, &claims).await?;
+    let user = usuarios.actualizar_perfil(antes.clone(), &datos).await?;
+
+    registrar_cambios(&req, &db, &antes, &user).await;
+
+    // Pedir un email distinto del actual deja siempre uno pendiente
+    let mensaje = match (&datos.email, &user.pending_email) {
+        (Some(_), Some(email)) => Mensaje::new(Clave::ConfirmacionEmailEnviada).con("email", email),
+        _ => Mensaje::new(Clave::PerfilActualizado),
+    };
+
+    let etag = etag_usuario(&user);
+    Ok(ApiResponse::new(Perfil::from(user)).mensaje(mensaje).cabecera(ETag(etag)))
+}
+
+/// Confirma el email pendiente con el código enviado
+#[utoipa::path(post, path = "/api/perfil/email/confirmar", tag = "perfil", security(("bearer" = [])),
+    request_body = ConfirmEmailDto,
+    responses(
+        (status = 200, description = "Email confirmado", body = Perfil),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 409, description = "Otro usuario usa ya ese email", body = ErrorResponse),
+        (status = 422, description = "Código inválido o caducado", body = ErrorResponse),
+    ),
+)]
+pub async fn confirm_email(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    datos: web::Json<ConfirmEmailDto>,
+) -> Result<ApiResponse<Perfil>, ApiError> {
+    let antes = usuario_autenticado(&usuarios, &claims).await?;
+    let user = usuarios.confirmar_email(antes.clone(), &datos.token).await?;
+
+    registrar_cambios(&req, &db, &antes, &user).await;
+
+    let etag = etag_usuario(&user);
+    Ok(ApiResponse::new(Perfil::from(user)).mensaje(Clave::EmailConfirmado).cabecera(ETag(etag)))
+}
+
+/// Cambia la contraseña del usuario autenticado
+#[utoipa::path(post, path = "/api/perfil/contrasena", tag = "perfil", security(("bearer" = [])),
+    request_body = ChangePasswordDto,
+    responses(
+        (status = 200, description = "Contraseña cambiada", body = Object),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 422, description = "Contraseña actual incorrecta o la nueva no cumple la política", body = ErrorResponse),
+    ),
+)]
+pub async fn change_password(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    datos: web::Json<ChangePasswordDto>,
+) -> Result<ApiResponse<()>, ApiError> {
+    let usuario = usuario_autenticado(&usuarios, &claims).await?;
+    let user = usuarios
+        .cambiar_contraseña(usuario, &datos.current_password, &datos.new_password)
+        .await?;
+
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::PASSWORD_CHANGED).actor(user.id).target(user.id),
+    ).await;
+
+    Ok(ApiResponse::new(())
+        .mensaje(Clave::ContraseñaCambiada)
+        .legado(serde_json::json!({ "message": Mensaje::new(Clave::ContraseñaCambiada) })))
+}
+
+/// Elimina la cuenta del usuario autenticado (borrado lógico)
+#[utoipa::path(delete, path = "/api/perfil", tag = "perfil", security(("bearer" = [])),
+    request_body = DeleteAccountDto,
+    responses(
+        (status = 200, description = "Cuenta eliminada", body = Object),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 422, description = "Contraseña incorrecta", body = ErrorResponse),
+    ),
+)]
+pub async fn delete_profile(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    datos: web::Json<DeleteAccountDto>,
+) -> Result<ApiResponse<()>, ApiError> {
+    let usuario = usuario_autenticado(&usuarios, &claims).await?;
+    let user = usuarios.eliminar_cuenta(usuario, &datos.password).await?;
+
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::USER_DELETED).actor(user.id).target(user.id),
+    ).await;
+
+    Ok(ApiResponse::new(())
+        .mensaje(Clave::CuentaEliminada)
+        .legado(serde_json::json!({ "message": Mensaje::new(Clave::CuentaEliminada) })))
+}
+
+// Usuario del token, leído de la primaria antes de modificarlo
+async fn usuario_autenticado(usuarios: &UserService, claims: &Claims) -> Result<User, ApiError> {
+    let usuario_id = claims
+        .usuario_id()
+        .ok_or_else(|| ApiError::bad_request(Clave::IdUsuarioInvalido))?;
+    usuarios.buscar(usuario_id).await
+}
+
+// Audita los cambios del propio perfil, si los hubo
+async fn registrar_cambios(req: &HttpRequest, db: &DatabaseConnection, antes: &User, despues: &User) {
+    if let Some(cambios) = diff_usuario(antes, despues) {
+        audit::registrar(
+            db,
+            req,
+            AuditEvent::new(audit_log::USER_UPDATED)
+                .actor(despues.id)
+                .target(despues.id)
+                .cambios(cambios),
+        ).await;
     }
 }
 
+/// Lista los usuarios no eliminados
+#[utoipa::path(get, path = "/api/usuarios", tag = "usuarios", security(("bearer" = [])), responses(
+    (status = 200, description = "Usuarios", body = Vec<User>),
+    (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+))]
 pub async fn get_users(
-    db: web::Data<DatabaseConnection>,
-) -> Result<HttpResponse, ApiError> {
-    let users = UserEntity::find()
-        .all(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
+    usuarios: web::Data<UserService>,
+) -> Result<ApiResponse<Vec<User>>, ApiError> {
+    let users = usuarios.listar().await?;
 
-    Ok(HttpResponse::Ok().json(users))
+    Ok(ApiResponse::new(users))
 }
 
+/// Obtiene un usuario por id
+#[utoipa::path(get, path = "/api/usuarios/{id}", tag = "usuarios", security(("bearer" = [])),
+    params(("id" = i32, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Usuario, con su `ETag`", body = User),
+        (status = 304, description = "Sin cambios respecto a `If-None-Match`"),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 404, description = "Usuario no encontrado", body = ErrorResponse),
+    ),
+)]
 pub async fn get_user(
-    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    usuarios: web::Data<UserService>,
     id: web::Path<i32>,
-) -> Result<HttpResponse, ApiError> {
-    let user = UserEntity::find_by_id(*id)
-        .one(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-
-    match user {
-        Some(user) => Ok(HttpResponse::Ok().json(user)),
-        None => Err(ApiError::not_found("Usuario no encontrado".to_string())),
+) -> Result<Either<HttpResponse, ApiResponse<User>>, ApiError> {
+    let user = usuarios.obtener(*id).await?;
+    let etag = etag_usuario(&user);
+
+    if no_modificado(&req, &etag) {
+        return Ok(Either::Left(HttpResponse::NotModified().insert_header(ETag(etag)).finish()));
     }
+
+    Ok(Either::Right(ApiResponse::new(user).cabecera(ETag(etag))))
 }
 
+/// Registra un usuario nuevo
+#[utoipa::path(post, path = "/api/auth/registro", tag = "auth", request_body = CreateUserDto, responses(
+    (status = 201, description = "Usuario creado", body = User),
+    (status = 409, description = "El email ya está en uso", body = ErrorResponse),
+    (status = 422, description = "La contraseña no cumple la política", body = ErrorResponse),
+))]
 pub async fn create_user(
+    req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
     user_data: web::Json<CreateUserDto>,
-) -> Result<HttpResponse, ApiError> {
-    let hashed_password = hash_password(&user_data.password)
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-
-    let user = crate::models::user::ActiveModel {
-        name: Set(user_data.name.clone()),
-        email: Set(user_data.email.clone()),
-        password: Set(hashed_password),
-        created_at: Set(Utc::now()),
-        updated_at: Set(Utc::now()),
-        ..Default::default()
-    };
+) -> Result<ApiResponse<User>, ApiError> {
+    let user = usuarios.registrar(&user_data, ROLE_USER).await?;
 
-    let user: UserModel = user.insert(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::USER_REGISTERED)
+            .actor(user.id)
+            .target(user.id)
+            .cambios(serde_json::json!({ "name": user.name, "email": user.email })),
+    ).await;
 
-    Ok(HttpResponse::Created().json(user))
+    Ok(ApiResponse::new(user).estado(StatusCode::CREATED))
 }
 
+/// Actualiza un usuario
+#[utoipa::path(put, path = "/api/usuarios/{id}", tag = "usuarios", security(("bearer" = [])),
+    params(
+        ("id" = i32, Path, description = "Id del usuario"),
+        ("If-Match" = Option<String>, Header, description = "ETag leído; obligatorio con `REQUIRE_IF_MATCH`"),
+    ),
+    request_body = UpdateUserDto,
+    responses(
+        (status = 200, description = "Usuario actualizado, con su nuevo `ETag`", body = User),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 403, description = "Solo el propio usuario o un administrador", body = ErrorResponse),
+        (status = 404, description = "Usuario no encontrado", body = ErrorResponse),
+        (status = 409, description = "El email ya está en uso", body = ErrorResponse),
+        (status = 412, description = "El usuario cambió desde que se leyó", body = ErrorResponse),
+        (status = 422, description = "Error de validación; solo un administrador puede cambiar el email o la contraseña", body = ErrorResponse),
+        (status = 428, description = "Falta `If-Match`", body = ErrorResponse),
+    ),
+)]
 pub async fn update_user(
+    req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    actor: web::ReqData<User>,
     id: web::Path<i32>,
     user_data: web::Json<UpdateUserDto>,
-) -> Result<HttpResponse, ApiError> {
-    let user = UserEntity::find_by_id(*id)
-        .one(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-
-    let mut user = match user {
-        Some(user) => user.into_active_model(),
-        None => return Err(ApiError::not_found("Usuario no encontrado".to_string())),
-    };
+) -> Result<ApiResponse<User>, ApiError> {
+    usuarios.comprobar_acceso(&actor, *id)?;
+    let antes = usuarios.buscar(*id).await?;
+    verificar_if_match(&req, &etag_usuario(&antes))?;
 
-    if let Some(name) = &user_data.name {
-        user.name = Set(name.clone());
-    }
-    
-    if let Some(email) = &user_data.email {
-        user.email = Set(email.clone());
+    let user = usuarios.actualizar(antes.clone(), &user_data, actor.is_admin()).await?;
+
+    if let Some(cambios) = diff_usuario(&antes, &user) {
+        audit::registrar(
+            db.get_ref(),
+            &req,
+            AuditEvent::new(audit_log::USER_UPDATED)
+                .actor(claims.usuario_id())
+                .target(user.id)
+                .cambios(cambios),
+        ).await;
     }
-    
-    if let Some(password) = &user_data.password {
-        let hashed_password = hash_password(password)
-            .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-        user.password = Set(hashed_password);
+
+    if user_data.password.is_some() {
+        audit::registrar(
+            db.get_ref(),
+            &req,
+            AuditEvent::new(audit_log::PASSWORD_CHANGED)
+                .actor(claims.usuario_id())
+                .target(user.id),
+        ).await;
     }
 
-    user.updated_at = Set(Utc::now());
+    let etag = etag_usuario(&user);
+    Ok(ApiResponse::new(user).cabecera(ETag(etag)))
+}
+
+/// Modifica un usuario con JSON Merge Patch o JSON Patch
+#[utoipa::path(patch, path = "/api/usuarios/{id}", tag = "usuarios", security(("bearer" = [])),
+    params(
+        ("id" = i32, Path, description = "Id del usuario"),
+        ("If-Match" = Option<String>, Header, description = "ETag leído; obligatorio con `REQUIRE_IF_MATCH`"),
+    ),
+    request_body(content(
+        (Object = "application/merge-patch+json"),
+        (Vec<Object> = "application/json-patch+json"),
+    )),
+    responses(
+        (status = 200, description = "Usuario modificado, con su nuevo `ETag`", body = User),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 403, description = "Solo el propio usuario o un administrador", body = ErrorResponse),
+        (status = 404, description = "Usuario no encontrado", body = ErrorResponse),
+        (status = 409, description = "Email en uso o una operación `test` no se cumple", body = ErrorResponse),
+        (status = 412, description = "El usuario cambió desde que se leyó", body = ErrorResponse),
+        (status = 415, description = "`Content-Type` no soportado", body = ErrorResponse),
+        (status = 422, description = "Campo protegido, resultado inválido o cambio de email sin ser administrador", body = ErrorResponse),
+    ),
+)]
+pub async fn patch_user(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    actor: web::ReqData<User>,
+    id: web::Path<i32>,
+    cuerpo: web::Bytes,
+) -> Result<ApiResponse<User>, ApiError> {
+    usuarios.comprobar_acceso(&actor, *id)?;
+    let antes = usuarios.buscar(*id).await?;
+    verificar_if_match(&req, &etag_usuario(&antes))?;
+
+    // El parche se escribe con los nombres de las respuestas (`createdAt` con
+    // camelCase en la v2) y se lee con los del modelo
+    let campos = FormatoRespuesta::actual().convencion();
+    let mut documento = campos.aplicar(&antes)
+        .map_err(ApiError::interno)?;
+    patch::aplicar(&req, &mut documento, &cuerpo)?;
+    let documento = campos.leer(documento);
+
+    let user = usuarios.parchear(antes.clone(), &documento, actor.is_admin()).await?;
 
-    let user: UserModel = user.update(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
+    if let Some(cambios) = diff_usuario(&antes, &user) {
+        audit::registrar(
+            db.get_ref(),
+            &req,
+            AuditEvent::new(audit_log::USER_UPDATED)
+                .actor(claims.usuario_id())
+                .target(user.id)
+                .cambios(cambios),
+        ).await;
+    }
 
-    Ok(HttpResponse::Ok().json(user))
+    let etag = etag_usuario(&user);
+    Ok(ApiResponse::new(user).cabecera(ETag(etag)))
 }
 
+/// Elimina un usuario (borrado lógico)
+#[utoipa::path(delete, path = "/api/usuarios/{id}", tag = "usuarios", security(("bearer" = [])),
+    params(
+        ("id" = i32, Path, description = "Id del usuario"),
+        ("If-Match" = Option<String>, Header, description = "ETag leído; obligatorio con `REQUIRE_IF_MATCH`"),
+    ),
+    responses(
+        (status = 200, description = "Usuario eliminado", body = Object),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 403, description = "Solo el propio usuario o un administrador", body = ErrorResponse),
+        (status = 404, description = "Usuario no encontrado", body = ErrorResponse),
+        (status = 412, description = "El usuario cambió desde que se leyó", body = ErrorResponse),
+    ),
+)]
 pub async fn delete_user(
+    req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    actor: web::ReqData<User>,
     id: web::Path<i32>,
-) -> Result<HttpResponse, ApiError> {
-    let user = UserEntity::find_by_id(*id)
-        .one(db.get_ref())
-        .await
-        .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-
-    match user {
-        Some(user) => {
-            user.delete(db.get_ref())
-                .await
-                .map_err(|e| ApiError::internal_server_error(e.to_string()))?;
-            
-            Ok(HttpResponse::Ok().json(serde_json::json!({
-                "message": "Usuario eliminado exitoxamente"
-            })))
-        }
-        None => Err(ApiError::not_found("Usuario no encontrado".to_string())),
-    }
-}
\ No newline at end of file
+) -> Result<ApiResponse<()>, ApiError> {
+    usuarios.comprobar_acceso(&actor, *id)?;
+    let user = usuarios.buscar(*id).await?;
+    verificar_if_match(&req, &etag_usuario(&user))?;
+
+    let user = usuarios.eliminar(user).await?;
+
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::USER_DELETED)
+            .actor(claims.usuario_id())
+            .target(user.id),
+    ).await;
+
+    Ok(ApiResponse::new(())
+        .mensaje(Clave::UsuarioEliminado)
+        .legado(serde_json::json!({ "message": Mensaje::new(Clave::UsuarioEliminado) })))
+}
+
+/// Restaura un usuario eliminado (administrador)
+#[utoipa::path(post, path = "/api/usuarios/{id}/restaurar", tag = "usuarios", security(("bearer" = [])),
+    params(("id" = i32, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Usuario restaurado", body = User),
+        (status = 400, description = "El usuario no está eliminado", body = ErrorResponse),
+        (status = 401, description = "Token inválido o faltante", body = ErrorResponse),
+        (status = 403, description = "Se requieren permisos de administrador", body = ErrorResponse),
+        (status = 404, description = "Usuario no encontrado", body = ErrorResponse),
+        (status = 409, description = "Otro usuario usa ya su email", body = ErrorResponse),
+    ),
+)]
+pub async fn restore_user(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    usuarios: web::Data<UserService>,
+    claims: web::ReqData<Claims>,
+    id: web::Path<i32>,
+) -> Result<ApiResponse<User>, ApiError> {
+    let admin = usuarios.requerir_admin(&claims).await?;
+    let user = usuarios.restaurar(*id).await?;
+
+    audit::registrar(
+        db.get_ref(),
+        &req,
+        AuditEvent::new(audit_log::USER_RESTORED).actor(admin.id).target(user.id),
+    ).await;
+
+    let etag = etag_usuario(&user);
+    Ok(ApiResponse::new(user).cabecera(ETag(etag)))
+}
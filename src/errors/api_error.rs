@@ -1,32 +1,115 @@
 use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
 use std::fmt;
+use utoipa::ToSchema;
+
+use crate::i18n::{Clave, Mensaje};
+use crate::middleware::api_version::FormatoRespuesta;
+use crate::middleware::request_id::request_id_actual;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorCampo {
+    #[serde(rename = "field")]
+    pub campo: String,
+    #[serde(rename = "message")]
+    #[schema(value_type = String)]
+    pub mensaje: Mensaje,
+}
+
+impl ErrorCampo {
+    pub fn new(campo: &str, mensaje: impl Into<Mensaje>) -> Self {
+        Self { campo: campo.to_string(), mensaje: mensaje.into() }
+    }
+}
+
+/// Cuerpo JSON de todas las respuestas de error.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub success: bool,
+    /// Mensaje en el idioma de la solicitud (`Accept-Language`).
+    pub message: String,
+    /// Clave del mensaje en el catálogo, igual en todos los idiomas.
+    pub code: &'static str,
+    pub status_code: u16,
+    /// Errores de validación por campo (solo en `422`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorCampo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
 
 #[derive(Debug)]
 pub struct ApiError {
-    pub mensaje: String,
+    pub mensaje: Mensaje,
     pub codigo_estado: u16,
+    pub errores: Vec<ErrorCampo>,
 }
 
 impl ApiError {
-    pub fn new(mensaje: String, codigo_estado: u16) -> Self {
-        Self { mensaje, codigo_estado }
+    pub fn new(mensaje: impl Into<Mensaje>, codigo_estado: u16) -> Self {
+        Self { mensaje: mensaje.into(), codigo_estado, errores: Vec::new() }
     }
 
-    pub fn internal_server_error(mensaje: String) -> Self {
+    pub fn internal_server_error(mensaje: impl Into<Mensaje>) -> Self {
         Self::new(mensaje, 500)
     }
 
-    pub fn not_found(mensaje: String) -> Self {
+    /// Error inesperado: el detalle va al log y el cliente recibe un mensaje genérico.
+    pub fn interno(error: impl fmt::Display) -> Self {
+        tracing::error!("Error interno: {}", error);
+        Self::internal_server_error(Clave::ErrorInterno)
+    }
+
+    pub fn not_found(mensaje: impl Into<Mensaje>) -> Self {
         Self::new(mensaje, 404)
     }
 
-    pub fn bad_request(mensaje: String) -> Self {
+    pub fn bad_request(mensaje: impl Into<Mensaje>) -> Self {
         Self::new(mensaje, 400)
     }
 
-    pub fn unauthorized(mensaje: String) -> Self {
+    pub fn unauthorized(mensaje: impl Into<Mensaje>) -> Self {
         Self::new(mensaje, 401)
     }
+
+    pub fn forbidden(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 403)
+    }
+
+    pub fn not_acceptable(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 406)
+    }
+
+    pub fn conflict(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 409)
+    }
+
+    pub fn precondition_failed(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 412)
+    }
+
+    pub fn precondition_required(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 428)
+    }
+
+    pub fn unsupported_media_type(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 415)
+    }
+
+    pub fn unprocessable_entity(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 422)
+    }
+
+    pub fn service_unavailable(mensaje: impl Into<Mensaje>) -> Self {
+        Self::new(mensaje, 503)
+    }
+
+    pub fn validation(errores: Vec<ErrorCampo>) -> Self {
+        Self {
+            errores,
+            ..Self::unprocessable_entity(Clave::ErrorValidacion)
+        }
+    }
 }
 
 impl fmt::Display for ApiError {
@@ -36,14 +119,32 @@ impl fmt::Display for ApiError {
 }
 
 impl ResponseError for ApiError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::from_u16(self.codigo_estado)
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
     fn error_response(&self) -> HttpResponse {
-        let status = actix_web::http::StatusCode::from_u16(self.codigo_estado)
-            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+        // Desde la v2 los errores siguen la convención de campos de las
+        // respuestas, también en los nombres de `errors[].field`
+        let campos = FormatoRespuesta::actual().convencion();
 
-        HttpResponse::build(status).json(serde_json::json!({
-            "success": false,
-            "message": self.mensaje,
-            "status_code": self.codigo_estado
-        }))
+        let cuerpo = ErrorResponse {
+            success: false,
+            message: self.mensaje.to_string(),
+            code: self.mensaje.clave.codigo(),
+            status_code: self.codigo_estado,
+            errors: self
+                .errores
+                .iter()
+                .map(|error| ErrorCampo { campo: campos.nombre(&error.campo), ..error.clone() })
+                .collect(),
+            request_id: request_id_actual(),
+        };
+
+        match campos.aplicar(&cuerpo) {
+            Ok(valor) => HttpResponse::build(self.status_code()).json(valor),
+            Err(_) => HttpResponse::build(self.status_code()).json(cuerpo),
+        }
     }
-}
\ No newline at end of file
+}
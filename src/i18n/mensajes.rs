@@ -0,0 +1,402 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+use super::{idioma_actual, Idioma};
+
+/// Claves del catálogo de mensajes. Cada una tiene un identificador estable,
+/// que se envía como `code` en los errores, y su texto en cada idioma.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clave {
+    // Errores generales
+    ErrorInterno,
+    ErrorValidacion,
+    TokenInvalido,
+    IdUsuarioInvalido,
+    SeRequiereAdmin,
+    SoloPropioUsuario,
+    RecursoModificado,
+    IfMatchRequerido,
+    VersionNoSoportada,
+    // Autenticación
+    CredencialesRequeridas,
+    CredencialesInvalidas,
+    CuentaDeshabilitada,
+    InicioSesionExitoso,
+    // Usuarios
+    UsuarioNoEncontrado,
+    UsuarioNoEliminado,
+    UsuarioEliminado,
+    EmailEnUso,
+    PerfilObtenido,
+    PerfilActualizado,
+    ConfirmacionEmailEnviada,
+    EmailConfirmado,
+    TokenEmailInvalido,
+    ContraseñaActualIncorrecta,
+    ContraseñaCambiada,
+    CuentaEliminada,
+    EmailSoloDesdePerfil,
+    ContraseñaSoloDesdePerfil,
+    // Correo
+    CorreoNoDisponible,
+    AsuntoConfirmacionEmail,
+    CuerpoConfirmacionEmail,
+    // Validación de campos
+    NombreRequerido,
+    EmailInvalido,
+    IdiomaNoSoportado,
+    CampoNoModificable,
+    CampoDesconocido,
+    ContraseñaCorta,
+    ContraseñaSinMayuscula,
+    ContraseñaSinMinuscula,
+    ContraseñaSinNumero,
+    ContraseñaSinSimbolo,
+    ContraseñaConDatosPersonales,
+    ContraseñaFiltrada,
+    // PATCH
+    MergePatchInvalido,
+    JsonPatchInvalido,
+    PruebaPatchFallida,
+    PatchNoAplicable,
+    TipoPatchNoSoportado,
+    // Salud
+    ServidorFuncionando,
+}
+
+impl Clave {
+    pub const TODAS: [Clave; 48] = [
+        Clave::ErrorInterno,
+        Clave::ErrorValidacion,
+        Clave::TokenInvalido,
+        Clave::IdUsuarioInvalido,
+        Clave::SeRequiereAdmin,
+        Clave::SoloPropioUsuario,
+        Clave::RecursoModificado,
+        Clave::IfMatchRequerido,
+        Clave::VersionNoSoportada,
+        Clave::CredencialesRequeridas,
+        Clave::CredencialesInvalidas,
+        Clave::CuentaDeshabilitada,
+        Clave::InicioSesionExitoso,
+        Clave::UsuarioNoEncontrado,
+        Clave::UsuarioNoEliminado,
+        Clave::UsuarioEliminado,
+        Clave::EmailEnUso,
+        Clave::PerfilObtenido,
+        Clave::PerfilActualizado,
+        Clave::ConfirmacionEmailEnviada,
+        Clave::EmailConfirmado,
+        Clave::TokenEmailInvalido,
+        Clave::ContraseñaActualIncorrecta,
+        Clave::ContraseñaCambiada,
+        Clave::CuentaEliminada,
+        Clave::EmailSoloDesdePerfil,
+        Clave::ContraseñaSoloDesdePerfil,
+        Clave::CorreoNoDisponible,
+        Clave::AsuntoConfirmacionEmail,
+        Clave::CuerpoConfirmacionEmail,
+        Clave::NombreRequerido,
+        Clave::EmailInvalido,
+        Clave::IdiomaNoSoportado,
+        Clave::CampoNoModificable,
+        Clave::CampoDesconocido,
+        Clave::ContraseñaCorta,
+        Clave::ContraseñaSinMayuscula,
+        Clave::ContraseñaSinMinuscula,
+        Clave::ContraseñaSinNumero,
+        Clave::ContraseñaSinSimbolo,
+        Clave::ContraseñaConDatosPersonales,
+        Clave::ContraseñaFiltrada,
+        Clave::MergePatchInvalido,
+        Clave::JsonPatchInvalido,
+        Clave::PruebaPatchFallida,
+        Clave::PatchNoAplicable,
+        Clave::TipoPatchNoSoportado,
+        Clave::ServidorFuncionando,
+    ];
+
+    /// Identificador estable, p. ej. `user.not_found`.
+    pub fn codigo(self) -> &'static str {
+        self.entrada().0
+    }
+
+    /// Texto en `idioma`, con marcadores `{nombre}` para los parámetros.
+    pub fn texto(self, idioma: Idioma) -> &'static str {
+        let (_, es, en) = self.entrada();
+        match idioma {
+            Idioma::Es => es,
+            Idioma::En => en,
+        }
+    }
+
+    // (código, español, inglés)
+    fn entrada(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Clave::ErrorInterno => (
+                "internal_error",
+                "Error interno del servidor",
+                "Internal server error",
+            ),
+            Clave::ErrorValidacion => ("validation_error", "Error de validación", "Validation error"),
+            Clave::TokenInvalido => (
+                "auth.invalid_token",
+                "Token inválido o faltante",
+                "Missing or invalid token",
+            ),
+            Clave::IdUsuarioInvalido => ("user.invalid_id", "ID de usuario inválido", "Invalid user ID"),
+            Clave::SeRequiereAdmin => (
+                "auth.admin_required",
+                "Se requieren permisos de administrador",
+                "Administrator permissions are required",
+            ),
+            Clave::SoloPropioUsuario => (
+                "auth.owner_required",
+                "Solo puedes modificar tu propio usuario",
+                "You can only modify your own user",
+            ),
+            Clave::RecursoModificado => (
+                "precondition_failed",
+                "El recurso fue modificado por otra solicitud",
+                "The resource was modified by another request",
+            ),
+            Clave::IfMatchRequerido => (
+                "precondition_required",
+                "Se requiere la cabecera If-Match",
+                "The If-Match header is required",
+            ),
+            Clave::VersionNoSoportada => (
+                "api.unsupported_version",
+                "Versión de la API no soportada: {version}. Versiones disponibles: {disponibles}",
+                "Unsupported API version: {version}. Available versions: {disponibles}",
+            ),
+            Clave::CredencialesRequeridas => (
+                "auth.credentials_required",
+                "El email y la contraseña son requeridos",
+                "Email and password are required",
+            ),
+            Clave::CredencialesInvalidas => (
+                "auth.invalid_credentials",
+                "Credenciales inválidas",
+                "Invalid credentials",
+            ),
+            Clave::CuentaDeshabilitada => (
+                "auth.account_disabled",
+                "La cuenta está deshabilitada",
+                "The account is disabled",
+            ),
+            Clave::InicioSesionExitoso => (
+                "auth.login_succeeded",
+                "Inicio de sesión exitoso",
+                "Logged in successfully",
+            ),
+            Clave::UsuarioNoEncontrado => ("user.not_found", "Usuario no encontrado", "User not found"),
+            Clave::UsuarioNoEliminado => (
+                "user.not_deleted",
+                "El usuario no está eliminado",
+                "The user is not deleted",
+            ),
+            Clave::UsuarioEliminado => (
+                "user.deleted",
+                "Usuario eliminado exitosamente",
+                "User deleted successfully",
+            ),
+            Clave::EmailEnUso => (
+                "user.email_taken",
+                "Ya existe un usuario con ese email",
+                "A user with that email already exists",
+            ),
+            Clave::PerfilObtenido => (
+                "profile.retrieved",
+                "Perfil obtenido exitosamente",
+                "Profile retrieved successfully",
+            ),
+            Clave::PerfilActualizado => (
+                "profile.updated",
+                "Perfil actualizado exitosamente",
+                "Profile updated successfully",
+            ),
+            Clave::ConfirmacionEmailEnviada => (
+                "profile.email_confirmation_sent",
+                "Te enviamos un código para confirmar {email}; el email no cambia hasta confirmarlo",
+                "We sent a code to confirm {email}; the email does not change until it is confirmed",
+            ),
+            Clave::EmailConfirmado => (
+                "profile.email_confirmed",
+                "Email confirmado exitosamente",
+                "Email confirmed successfully",
+            ),
+            Clave::TokenEmailInvalido => (
+                "profile.invalid_email_token",
+                "El código de confirmación no es válido o caducó",
+                "The confirmation code is invalid or has expired",
+            ),
+            Clave::ContraseñaActualIncorrecta => (
+                "profile.wrong_password",
+                "La contraseña actual no es correcta",
+                "The current password is not correct",
+            ),
+            Clave::ContraseñaCambiada => (
+                "profile.password_changed",
+                "Contraseña cambiada exitosamente",
+                "Password changed successfully",
+            ),
+            Clave::CuentaEliminada => (
+                "profile.deleted",
+                "Cuenta eliminada exitosamente",
+                "Account deleted successfully",
+            ),
+            Clave::EmailSoloDesdePerfil => (
+                "field.email_requires_confirmation",
+                "El email se cambia desde /api/perfil y requiere confirmarlo",
+                "The email is changed through /api/perfil and must be confirmed",
+            ),
+            Clave::ContraseñaSoloDesdePerfil => (
+                "field.password_requires_current",
+                "La contraseña se cambia desde /api/perfil/contrasena con la contraseña actual",
+                "The password is changed through /api/perfil/contrasena with the current password",
+            ),
+            Clave::CorreoNoDisponible => (
+                "email.unavailable",
+                "No se pueden enviar correos en este momento",
+                "Emails cannot be sent right now",
+            ),
+            Clave::AsuntoConfirmacionEmail => (
+                "email.confirmation_subject",
+                "Confirma tu nuevo email",
+                "Confirm your new email",
+            ),
+            Clave::CuerpoConfirmacionEmail => (
+                "email.confirmation_body",
+                "Usa este código para confirmar tu nuevo email: {codigo}\n\nCaduca en 24 horas. Si no pediste el cambio, ignora este correo.",
+                "Use this code to confirm your new email: {codigo}\n\nIt expires in 24 hours. If you did not request the change, ignore this email.",
+            ),
+            Clave::NombreRequerido => ("field.name_required", "El nombre es requerido", "Name is required"),
+            Clave::EmailInvalido => ("field.invalid_email", "El email no es válido", "Email is not valid"),
+            Clave::IdiomaNoSoportado => (
+                "field.unsupported_locale",
+                "Idioma no soportado, usa {disponibles}",
+                "Unsupported locale, use {disponibles}",
+            ),
+            Clave::CampoNoModificable => (
+                "field.read_only",
+                "Este campo no se puede modificar",
+                "This field cannot be modified",
+            ),
+            Clave::CampoDesconocido => ("field.unknown", "Campo desconocido", "Unknown field"),
+            Clave::ContraseñaCorta => (
+                "password.too_short",
+                "La contraseña debe tener al menos {minimo} caracteres",
+                "The password must be at least {minimo} characters long",
+            ),
+            Clave::ContraseñaSinMayuscula => (
+                "password.missing_uppercase",
+                "La contraseña debe incluir al menos una letra mayúscula",
+                "The password must include at least one uppercase letter",
+            ),
+            Clave::ContraseñaSinMinuscula => (
+                "password.missing_lowercase",
+                "La contraseña debe incluir al menos una letra minúscula",
+                "The password must include at least one lowercase letter",
+            ),
+            Clave::ContraseñaSinNumero => (
+                "password.missing_digit",
+                "La contraseña debe incluir al menos un número",
+                "The password must include at least one number",
+            ),
+            Clave::ContraseñaSinSimbolo => (
+                "password.missing_symbol",
+                "La contraseña debe incluir al menos un símbolo",
+                "The password must include at least one symbol",
+            ),
+            Clave::ContraseñaConDatosPersonales => (
+                "password.personal_data",
+                "La contraseña no puede contener tu nombre ni tu email",
+                "The password cannot contain your name or email",
+            ),
+            Clave::ContraseñaFiltrada => (
+                "password.breached",
+                "Esta contraseña aparece en filtraciones de datos conocidas, elige otra",
+                "This password appears in known data breaches, choose another one",
+            ),
+            Clave::MergePatchInvalido => (
+                "patch.invalid_merge_patch",
+                "Merge patch inválido: {detalle}",
+                "Invalid merge patch: {detalle}",
+            ),
+            Clave::JsonPatchInvalido => (
+                "patch.invalid_json_patch",
+                "JSON Patch inválido: {detalle}",
+                "Invalid JSON Patch: {detalle}",
+            ),
+            Clave::PruebaPatchFallida => (
+                "patch.test_failed",
+                "La operación test {operacion} no se cumple en '{ruta}'",
+                "The test operation {operacion} does not hold at '{ruta}'",
+            ),
+            Clave::PatchNoAplicable => (
+                "patch.not_applicable",
+                "No se pudo aplicar el parche: {detalle}",
+                "The patch could not be applied: {detalle}",
+            ),
+            Clave::TipoPatchNoSoportado => (
+                "patch.unsupported_media_type",
+                "Content-Type no soportado, usa {tipos}",
+                "Unsupported Content-Type, use {tipos}",
+            ),
+            Clave::ServidorFuncionando => (
+                "health.ok",
+                "Servidor funcionando correctamente",
+                "Server is running",
+            ),
+        }
+    }
+}
+
+/// Mensaje del catálogo con sus parámetros, pendiente de traducir.
+///
+/// Se serializa y se muestra (`Display`) en el idioma de la solicitud en curso.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mensaje {
+    pub clave: Clave,
+    parametros: Vec<(&'static str, String)>,
+}
+
+impl Mensaje {
+    pub fn new(clave: Clave) -> Self {
+        Self { clave, parametros: Vec::new() }
+    }
+
+    /// Valor de un marcador `{nombre}` del texto.
+    pub fn con(mut self, nombre: &'static str, valor: impl fmt::Display) -> Self {
+        self.parametros.push((nombre, valor.to_string()));
+        self
+    }
+
+    pub fn traducir(&self, idioma: Idioma) -> String {
+        self.parametros
+            .iter()
+            .fold(self.clave.texto(idioma).to_string(), |texto, (nombre, valor)| {
+                texto.replace(&format!("{{{}}}", nombre), valor)
+            })
+    }
+}
+
+impl From<Clave> for Mensaje {
+    fn from(clave: Clave) -> Self {
+        Self::new(clave)
+    }
+}
+
+impl fmt::Display for Mensaje {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.traducir(idioma_actual()))
+    }
+}
+
+impl Serialize for Mensaje {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
@@ -0,0 +1,89 @@
+//! Mensajes de la API en español e inglés.
+//!
+//! Los errores y respuestas llevan una [`Clave`] del catálogo y se traducen al
+//! serializarse, con el idioma de la solicitud en curso: el de `Accept-Language`,
+//! el preferido del usuario autenticado (`users.locale`) o `DEFAULT_LOCALE`.
+mod mensajes;
+
+pub use mensajes::{Clave, Mensaje};
+
+use std::future::Future;
+use std::sync::LazyLock;
+
+use crate::config::env::var_opcional;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idioma {
+    Es,
+    En,
+}
+
+static IDIOMA_POR_DEFECTO: LazyLock<Idioma> = LazyLock::new(|| {
+    let Some(valor) = var_opcional("DEFAULT_LOCALE") else {
+        return Idioma::Es;
+    };
+    Idioma::desde_codigo(&valor).unwrap_or_else(|| {
+        tracing::warn!("DEFAULT_LOCALE no soportado: {}; se usa es", valor);
+        Idioma::Es
+    })
+});
+
+tokio::task_local! {
+    static IDIOMA_ACTUAL: Idioma;
+}
+
+impl Idioma {
+    pub const TODOS: [Idioma; 2] = [Idioma::Es, Idioma::En];
+
+    pub fn por_defecto() -> Self {
+        *IDIOMA_POR_DEFECTO
+    }
+
+    pub fn codigo(self) -> &'static str {
+        match self {
+            Idioma::Es => "es",
+            Idioma::En => "en",
+        }
+    }
+
+    /// Acepta el código del idioma con o sin región: `en`, `en-US`, `es_MX`.
+    pub fn desde_codigo(codigo: &str) -> Option<Self> {
+        let idioma = codigo.trim().split(['-', '_']).next()?;
+        Self::TODOS
+            .into_iter()
+            .find(|candidato| candidato.codigo().eq_ignore_ascii_case(idioma))
+    }
+
+    /// Idioma soportado con mayor `q` en una cabecera `Accept-Language`.
+    pub fn desde_accept_language(valor: &str) -> Option<Self> {
+        let mut mejor: Option<(Self, f32)> = None;
+
+        for rango in valor.split(',') {
+            let mut partes = rango.split(';').map(str::trim);
+            let Some(idioma) = partes.next().and_then(Self::desde_codigo) else {
+                continue;
+            };
+            let calidad = partes
+                .find_map(|parametro| parametro.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+
+            // Con igual `q` gana el primero de la lista
+            if calidad > 0.0 && mejor.is_none_or(|(_, maxima)| calidad > maxima) {
+                mejor = Some((idioma, calidad));
+            }
+        }
+
+        mejor.map(|(idioma, _)| idioma)
+    }
+}
+
+/// Idioma de la solicitud en curso; fuera de una solicitud, el idioma por defecto.
+pub fn idioma_actual() -> Idioma {
+    IDIOMA_ACTUAL.try_with(|idioma| *idioma).unwrap_or_else(|_| Idioma::por_defecto())
+}
+
+/// Ejecuta `tarea` con `idioma` como idioma actual.
+pub fn con_idioma<F: Future>(idioma: Idioma, tarea: F) -> impl Future<Output = F::Output> {
+    IDIOMA_ACTUAL.scope(idioma, tarea)
+}
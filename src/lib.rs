@@ -0,0 +1,23 @@
+//! API de usuarios con Actix-web y SeaORM.
+//!
+//! Además del binario, la biblioteca permite montar la aplicación en otros
+//! servicios o en tests: [`build_app`] construye la `App` con todas las rutas y
+//! middlewares, y [`run`] la sirve sobre un `TcpListener` ya enlazado.
+pub mod app;
+pub mod cli;
+pub mod config;
+pub mod controllers;
+pub mod errors;
+pub mod i18n;
+pub mod middleware;
+pub mod migrations;
+pub mod models;
+pub mod openapi;
+pub mod repositories;
+pub mod responses;
+pub mod routes;
+pub mod services;
+pub mod tasks;
+pub mod utils;
+
+pub use app::{build_app, run, AppConfig, AppState, ServerSettings};
@@ -1,34 +1,59 @@
-mod config;
-mod controllers;
-mod errors;
-mod middleware;
-mod models;
-mod routes;
-mod utils;
-
 use actix_web::{App, HttpServer, web::Data};
+use rust_api::{app, cli, config, routes, tasks, utils};
+use clap::Parser;
 use tracing_subscriber::prelude::*;
-
-use crate::middleware::cors::cors_config;
+use tracing_subscriber::{Layer, Registry};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Cargar variables de entorno desde .env
     dotenvy::dotenv().ok();
-    
-    // Inicializar tracing con formato personalizado
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .with(
-            tracing_subscriber::fmt::layer()
-                .compact()
-                .without_time()
-                .with_target(false)
-                .with_level(true)
-        )
-        .init();
+
+    // Sin subcomando (o con `serve`) se inicia el servidor; el resto son comandos de administración
+    let cli = cli::Cli::parse();
+    match cli.comando {
+        None | Some(cli::Comando::Serve) => servir().await,
+        Some(comando) => std::process::exit(cli::ejecutar(comando, cli.format).await),
+    }
+}
+
+async fn servir() -> std::io::Result<()> {
+    // Inicializar tracing: JSON con timestamps en producción, compacto en desarrollo
+    inicializar_logs();
+
+    // Política CORS: en producción no se permite cualquier origen con credenciales
+    let config_app = app::AppConfig::from_env();
+    if let Err(e) = config_app.cors.validar() {
+        tracing::error!("Configuración CORS inválida: {}", e);
+        std::process::exit(1);
+    }
+
+    if config_app.metricas == config::metrics::MetricsAccess::Publicas {
+        tracing::warn!("/metrics es público en el puerto de la API (METRICS_PUBLIC=true)");
+    }
+
+    // HTTPS opcional: TLS_CERT_PATH y TLS_KEY_PATH
+    #[cfg(not(feature = "tls"))]
+    if config::env::var_opcional("TLS_CERT_PATH").is_some() || config::env::var_opcional("TLS_KEY_PATH").is_some() {
+        tracing::error!("TLS_CERT_PATH o TLS_KEY_PATH está configurada pero el binario se compiló sin la feature `tls`");
+        std::process::exit(1);
+    }
+    #[cfg(feature = "tls")]
+    let tls = match config::tls::TlsSettings::from_env() {
+        Ok(tls) => tls,
+        Err(e) => {
+            tracing::error!("Configuración TLS inválida: {}", e);
+            std::process::exit(1);
+        }
+    };
+    #[cfg(feature = "tls")]
+    let config_tls = match tls.as_ref().map(utils::tls::configurar).transpose() {
+        Ok(config_tls) => config_tls,
+        Err(e) => {
+            tracing::error!("Error al cargar el certificado TLS: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Inicializar conexión a la base de datos
     let db = match config::database::connect().await {
@@ -42,6 +67,32 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // Réplica de lectura para los handlers GET (o la primaria si no hay réplica)
+    let replica = match config::database::connect_replica(&db).await {
+        Ok(replica) => replica,
+        Err(e) => {
+            tracing::error!("Error al conectar con la réplica de lectura: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Estado compartido del ciclo de vida: readiness y cancelación de tareas
+    let lifecycle = Data::new(utils::lifecycle::Lifecycle::default());
+    let ajustes_apagado = config::shutdown::ShutdownSettings::from_env();
+
+    // Purga periódica de usuarios eliminados
+    let purga = tasks::user_purge::iniciar(db.clone(), lifecycle.token_cancelacion());
+
+    // Recarga del certificado cuando cambia en disco
+    #[cfg(feature = "tls")]
+    let recarga_tls = tls.as_ref().zip(config_tls.as_ref()).map(|(ajustes, (_, certificado))| {
+        utils::tls::vigilar_certificado(certificado.clone(), ajustes.clone(), lifecycle.token_cancelacion())
+    });
+    #[cfg(not(feature = "tls"))]
+    let recarga_tls: Option<tokio::task::JoinHandle<()>> = None;
+
+    let tareas: Vec<_> = std::iter::once(purga).chain(recarga_tls).collect();
+
     // Obtener puerto del entorno o usar 8080 por defecto
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "8080".to_string())
@@ -49,18 +100,193 @@ async fn main() -> std::io::Result<()> {
         .expect("PORT debe ser un número");
 
     let addr = format!("0.0.0.0:{}", port);
-    tracing::info!("Iniciando servidor Actix-web en {}", addr);
-
-      // Crear servidor HTTP con CORS configurado correctamente
-    HttpServer::new(move || {
-        App::new()
-            .app_data(Data::new(db.clone()))
-            .wrap(cors_config())
-            .wrap(actix_web::middleware::Logger::default())
-            .wrap(middleware::auth::Authentication)
-            .configure(routes::config::config_routes)
-    })
-    .bind(&addr)?
-    .run()
-    .await
-}
\ No newline at end of file
+    #[cfg(feature = "tls")]
+    let esquema = if tls.is_some() { "https" } else { "http" };
+    #[cfg(not(feature = "tls"))]
+    let esquema = "http";
+    tracing::info!("Iniciando servidor Actix-web en {}://{}", esquema, addr);
+
+    // Con METRICS_PORT, /metrics se sirve solo en ese puerto de administración (ver `AppConfig`)
+    let metrics_port = config::env::var_opcional("METRICS_PORT")
+        .map(|valor| valor.parse::<u16>().expect("METRICS_PORT debe ser un número"));
+
+    // Servidores auxiliares: se detienen después del principal (ver `apagar_ordenadamente`)
+    let mut servidores_auxiliares = Vec::new();
+
+    if let Some(metrics_port) = metrics_port {
+        let metrics_addr = format!("0.0.0.0:{}", metrics_port);
+        tracing::info!("Exponiendo métricas en {}/metrics", metrics_addr);
+
+        let db = db.clone();
+        let replica = replica.clone();
+        let servidor_metricas = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(db.clone()))
+                .app_data(Data::new(replica.clone()))
+                .configure(routes::metrics_routes::config)
+        })
+        .workers(1)
+        .disable_signals()
+        .bind(&metrics_addr)?
+        .run();
+
+        servidores_auxiliares.push(servidor_metricas.handle());
+        actix_web::rt::spawn(servidor_metricas);
+    }
+
+    // Con TLS_REDIRECT_PORT, un servidor HTTP redirige todo a HTTPS
+    #[cfg(feature = "tls")]
+    if let Some(puerto_redireccion) = tls.as_ref().and_then(|tls| tls.puerto_redireccion) {
+        let redireccion_addr = format!("0.0.0.0:{}", puerto_redireccion);
+        tracing::info!("Redirigiendo http://{} a HTTPS", redireccion_addr);
+
+        let servidor_redireccion = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(utils::tls::PuertoHttps(port)))
+                .default_service(actix_web::web::to(utils::tls::redirigir_a_https))
+        })
+        .workers(1)
+        .disable_signals()
+        .bind(&redireccion_addr)?
+        .run();
+
+        servidores_auxiliares.push(servidor_redireccion.handle());
+        actix_web::rt::spawn(servidor_redireccion);
+    }
+
+    // Servidor principal: la misma aplicación que expone la biblioteca
+    let estado = app::AppState::new(db.clone(), replica.clone(), lifecycle.clone());
+    let mut ajustes_servidor = app::ServerSettings::new(config_app);
+    ajustes_servidor.timeout_apagado = ajustes_apagado.timeout;
+    #[cfg(feature = "tls")]
+    {
+        ajustes_servidor.tls = config_tls.map(|(config_tls, _)| config_tls);
+    }
+
+    let servidor = app::run(estado, ajustes_servidor, std::net::TcpListener::bind(&addr)?)?;
+
+    tokio::spawn(apagar_ordenadamente(lifecycle.clone(), ajustes_apagado, servidor.handle()));
+
+    let resultado = servidor.await;
+
+    // Las métricas siguen disponibles mientras se drena el servidor principal
+    for handle in servidores_auxiliares {
+        handle.stop(true).await;
+    }
+
+    // Fase 3: detener las tareas en segundo plano
+    tracing::info!("Apagado: deteniendo tareas en segundo plano");
+    lifecycle.cancelar_tareas();
+    for tarea in tareas {
+        if let Err(e) = tarea.await {
+            tracing::error!("Una tarea en segundo plano terminó con error: {}", e);
+        }
+    }
+
+    // Fase 4: cerrar los pools de conexiones
+    tracing::info!("Apagado: cerrando conexiones a la base de datos");
+    cerrar_pool("réplica", replica.conexion()).await;
+    cerrar_pool("primaria", &db).await;
+
+    #[cfg(feature = "otel")]
+    utils::telemetry::finalizar();
+
+    tracing::info!("Apagado completado");
+    resultado
+}
+
+// Fases 1 y 2 del apagado: readiness falla, se espera a que el balanceador
+// retire la instancia y se drenan las solicitudes en curso
+async fn apagar_ordenadamente(
+    lifecycle: Data<utils::lifecycle::Lifecycle>,
+    ajustes: config::shutdown::ShutdownSettings,
+    servidor: actix_web::dev::ServerHandle,
+) {
+    esperar_senal_apagado().await;
+
+    tracing::info!(
+        "Apagado: señal recibida; readiness marcada como no disponible durante {:?}",
+        ajustes.retraso_readiness
+    );
+    lifecycle.iniciar_apagado();
+
+    // Una segunda señal fuerza la salida sin esperar
+    tokio::spawn(async {
+        esperar_senal_apagado().await;
+        tracing::warn!("Apagado: segunda señal recibida; saliendo sin esperar");
+        std::process::exit(1);
+    });
+
+    tokio::time::sleep(ajustes.retraso_readiness).await;
+
+    tracing::info!(
+        "Apagado: drenando solicitudes en curso (máximo {:?})",
+        ajustes.timeout
+    );
+    servidor.stop(true).await;
+}
+
+async fn cerrar_pool(nombre: &str, db: &sea_orm::DatabaseConnection) {
+    if let Err(e) = db.close_by_ref().await {
+        tracing::error!("Error al cerrar el pool {}: {}", nombre, e);
+    }
+}
+
+// LOG_FORMAT=json|compact; por defecto JSON en producción (APP_ENV=production)
+fn inicializar_logs() {
+    let filtro = tracing_subscriber::EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+    );
+    let formato_json = match config::env::var_opcional("LOG_FORMAT") {
+        Some(formato) => formato.eq_ignore_ascii_case("json"),
+        None => config::environment::Environment::from_env().is_production(),
+    };
+
+    let formato: Box<dyn Layer<Registry> + Send + Sync> = if formato_json {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer()
+            .compact()
+            .without_time()
+            .with_target(false)
+            .with_level(true)
+            .boxed()
+    };
+
+    // Con la feature `otel`, los spans también se exportan por OTLP
+    #[cfg(feature = "otel")]
+    let otel = utils::telemetry::inicializar();
+    #[cfg(not(feature = "otel"))]
+    let otel: Option<tracing_subscriber::layer::Identity> = None;
+
+    tracing_subscriber::registry()
+        .with(formato)
+        .with(otel)
+        .with(filtro)
+        .init();
+}
+
+async fn esperar_senal_apagado() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+
+    #[cfg(unix)]
+    let terminar = async {
+        if let Ok(mut senal) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            senal.recv().await;
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminar = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminar => {},
+    }
+}
@@ -0,0 +1,233 @@
+use actix_web::{body::EitherBody, dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error, ResponseError};
+use actix_web::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use actix_web::http::Uri;
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::config::api_version::{
+    es_ruta_versionada, separar_version, ApiVersionSettings, TIPO_VERSIONADO, VERSIONES,
+    VERSION_ENVOLTORIO, VERSION_POR_DEFECTO,
+};
+use crate::config::field_case::FieldCase;
+use crate::errors::api_error::ApiError;
+use crate::i18n::{Clave, Mensaje};
+
+/// Versión de la API con la que se atendió la solicitud.
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// Formato de las respuestas de la solicitud en curso: la versión con la que
+/// se atiende y la convención de nombres de campo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatoRespuesta {
+    pub version: u16,
+    pub campos: FieldCase,
+}
+
+tokio::task_local! {
+    static FORMATO_ACTUAL: FormatoRespuesta;
+}
+
+impl FormatoRespuesta {
+    /// Formato de la solicitud en curso; fuera del middleware, el de la versión por defecto.
+    pub fn actual() -> Self {
+        FORMATO_ACTUAL.try_with(|formato| *formato).unwrap_or(Self {
+            version: VERSION_POR_DEFECTO,
+            campos: FieldCase::default(),
+        })
+    }
+
+    /// Desde la v2 las respuestas usan el envoltorio y la convención de campos.
+    pub fn envuelto(self) -> bool {
+        self.version >= VERSION_ENVOLTORIO
+    }
+
+    /// Convención de campos que se aplica: la configurada desde la v2 y
+    /// `snake_case` en la v1.
+    pub fn convencion(self) -> FieldCase {
+        if self.envuelto() { self.campos } else { FieldCase::SnakeCase }
+    }
+}
+
+/// Dirige las rutas sin versión (`/api/usuarios`) a `/api/v{n}`, con la versión
+/// pedida en `Accept` o la 1, y marca las respuestas de versiones obsoletas con
+/// `Deprecation` y `Sunset`.
+pub struct ApiVersioning {
+    ajustes: Rc<ApiVersionSettings>,
+    campos: FieldCase,
+}
+
+impl ApiVersioning {
+    pub fn new(ajustes: ApiVersionSettings, campos: FieldCase) -> Self {
+        Self { ajustes: Rc::new(ajustes), campos }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiVersioning
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ApiVersioningMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiVersioningMiddleware {
+            service,
+            ajustes: self.ajustes.clone(),
+            campos: self.campos,
+        }))
+    }
+}
+
+pub struct ApiVersioningMiddleware<S> {
+    service: S,
+    ajustes: Rc<ApiVersionSettings>,
+    campos: FieldCase,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiVersioningMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        // En la ruta manda la versión de la ruta; sin ella, la de `Accept`. Las
+        // sondas y la documentación no se reescriben, pero también la negocian
+        let (version, negociada) = match separar_version(req.path()) {
+            Some((version, _)) => (Some(version), false),
+            None if req.path().starts_with("/api/") => match version_aceptada(req.headers()) {
+                Ok(version) => {
+                    let version = version.unwrap_or(VERSION_POR_DEFECTO);
+                    if es_ruta_versionada(req.path()) {
+                        reescribir(&mut req, version);
+                    }
+                    (Some(version), true)
+                }
+                Err(e) => {
+                    return Box::pin(async move {
+                        Ok(req.into_response(e.error_response()).map_into_right_body())
+                    });
+                }
+            },
+            None => (None, false),
+        };
+
+        // Las versiones desconocidas (`/api/v9/...`) terminan en 404 sin cabeceras de versión
+        let version = version.filter(|version| VERSIONES.contains(version));
+        let formato = version.map(|version| FormatoRespuesta { version, campos: self.campos });
+        let ajustes = self.ajustes.clone();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = match formato {
+                Some(formato) => FORMATO_ACTUAL.scope(formato, fut).await?,
+                None => fut.await?,
+            };
+
+            if let Some(version) = version {
+                let cabeceras = res.headers_mut();
+                cabeceras.insert(HeaderName::from_static(API_VERSION_HEADER), HeaderValue::from(version));
+                if negociada {
+                    cabeceras.append(header::VARY, HeaderValue::from_static("Accept"));
+                }
+                marcar_obsolescencia(cabeceras, &ajustes, version);
+            }
+
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+// Busca `version=N` en los tipos `application/vnd.api+json` de `Accept`
+fn version_aceptada(cabeceras: &HeaderMap) -> Result<Option<u16>, ApiError> {
+    let tipos = cabeceras
+        .get_all(header::ACCEPT)
+        .filter_map(|valor| valor.to_str().ok())
+        .flat_map(|valor| valor.split(','));
+
+    for tipo in tipos {
+        let mut partes = tipo.split(';').map(str::trim);
+        if !partes.next().is_some_and(|nombre| nombre.eq_ignore_ascii_case(TIPO_VERSIONADO)) {
+            continue;
+        }
+
+        let Some(valor) = partes.find_map(|parametro| {
+            let (nombre, valor) = parametro.split_once('=')?;
+            nombre.trim().eq_ignore_ascii_case("version").then(|| valor.trim().trim_matches('"'))
+        }) else {
+            continue;
+        };
+
+        return match valor.parse::<u16>() {
+            Ok(version) if VERSIONES.contains(&version) => Ok(Some(version)),
+            _ => Err(ApiError::not_acceptable(
+                Mensaje::new(Clave::VersionNoSoportada)
+                    .con("version", valor)
+                    .con("disponibles", VERSIONES.map(|version| version.to_string()).join(", ")),
+            )),
+        };
+    }
+
+    Ok(None)
+}
+
+// Cambia `/api/...` por `/api/v{n}/...` antes de que el router resuelva la ruta
+fn reescribir(req: &mut ServiceRequest, version: u16) {
+    let uri = req.uri();
+    let ruta = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+    let nueva = format!("/api/v{}{}", version, &ruta["/api".len()..]);
+
+    let mut partes = uri.clone().into_parts();
+    partes.path_and_query = nueva.parse().ok();
+    let Ok(uri) = Uri::from_parts(partes) else {
+        return;
+    };
+
+    req.match_info_mut().get_mut().update(&uri);
+    req.head_mut().uri = uri;
+}
+
+// RFC 9745 (`Deprecation: @<segundos>`) y RFC 8594 (`Sunset: <fecha HTTP>`)
+fn marcar_obsolescencia(cabeceras: &mut HeaderMap, ajustes: &ApiVersionSettings, version: u16) {
+    let Some(obsolescencia) = ajustes.obsolescencia(version) else {
+        return;
+    };
+
+    let valores = [
+        (
+            HeaderName::from_static("deprecation"),
+            Some(format!("@{}", obsolescencia.desde.timestamp())),
+        ),
+        (
+            HeaderName::from_static("sunset"),
+            obsolescencia
+                .retirada
+                .map(|fecha| fecha.format("%a, %d %b %Y %H:%M:%S GMT").to_string()),
+        ),
+        (
+            header::LINK,
+            ajustes.enlace.as_ref().map(|enlace| format!("<{}>; rel=\"deprecation\"", enlace)),
+        ),
+    ];
+
+    for (nombre, valor) in valores {
+        if let Some(valor) = valor.and_then(|valor| HeaderValue::from_str(&valor).ok()) {
+            cabeceras.append(nombre, valor);
+        }
+    }
+}
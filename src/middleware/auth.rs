@@ -1,12 +1,28 @@
-use actix_web::{dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error, HttpMessage};
+use actix_web::{body::EitherBody, dev::{Service, ServiceRequest, ServiceResponse, Transform}, web::Data, Error, HttpMessage, ResponseError};
 use std::future::{ready, Ready};
 use std::pin::Pin;
+use std::rc::Rc;
 use std::task::{Context, Poll};
 
+use crate::config::api_version::sin_version;
+use crate::config::metrics::MetricsAccess;
 use crate::utils::jwt::validar_token;
 use crate::errors::api_error::ApiError;
+use crate::i18n::{con_idioma, Clave};
+use crate::middleware::locale::IdiomaSolicitud;
+use crate::services::UserService;
 
-pub struct Authentication;
+/// Exige un JWT válido salvo en las rutas públicas. `/metrics` sigue su propia
+/// regla (ver `MetricsAccess`).
+pub struct Authentication {
+    metricas: Rc<MetricsAccess>,
+}
+
+impl Authentication {
+    pub fn new(metricas: MetricsAccess) -> Self {
+        Self { metricas: Rc::new(metricas) }
+    }
+}
 
 impl<S, B> Transform<S, ServiceRequest> for Authentication
 where
@@ -14,19 +30,20 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Transform = AuthenticationMiddleware<S>;
     type InitError = ();
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthenticationMiddleware { service }))
+        ready(Ok(AuthenticationMiddleware { service: Rc::new(service), metricas: self.metricas.clone() }))
     }
 }
 
 pub struct AuthenticationMiddleware<S> {
-    service: S,
+    service: Rc<S>,
+    metricas: Rc<MetricsAccess>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthenticationMiddleware<S>
@@ -35,7 +52,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
 
@@ -44,51 +61,89 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Saltar autenticación para rutas públicas
-        let path = req.path();
+        // Identidad del certificado de cliente (mTLS), si la conexión lo presentó
+        #[cfg(feature = "tls")]
+        if let Some(principal) = req.conn_data::<crate::utils::tls::ClientPrincipal>().cloned() {
+            tracing::debug!(
+                sujeto = %principal.sujeto,
+                nombre_comun = principal.nombre_comun.as_deref(),
+                "Certificado de cliente presentado"
+            );
+            req.extensions_mut().insert(principal);
+        }
+
+        // Saltar autenticación para rutas públicas, en cualquier versión
+        let path = sin_version(req.path());
         
         let rutas_publicas = [
             "/api/salud",
+            "/api/salud/vivo",
+            "/api/salud/listo",
             "/api/info",
             "/api/auth/login",
-            "/api/auth/registro"
+            "/api/auth/registro",
+            "/api/openapi.json",
+            "/api/docs",
         ];
 
-        if rutas_publicas.contains(&path) {
+        // `/metrics` no lleva JWT: es pública con `METRICS_PUBLIC` o pide `METRICS_TOKEN`
+        if path == "/metrics" && self.metricas.en_app() {
+            let authorization = req.headers().get("Authorization").and_then(|valor| valor.to_str().ok());
+            if !self.metricas.permite(authorization) {
+                let api_error = ApiError::unauthorized(Clave::TokenInvalido);
+                return Box::pin(async move {
+                    Ok(req.into_response(api_error.error_response()).map_into_right_body())
+                });
+            }
+        }
+
+        // `/api/docs/...` son los archivos de Swagger UI
+        if path == "/metrics" || rutas_publicas.contains(&path.as_ref()) || path.starts_with("/api/docs/") {
             let fut = self.service.call(req);
             return Box::pin(async move {
                 let res = fut.await?;
-                Ok(res)
+                Ok(res.map_into_left_body())
             });
         }
 
-         // Extraer y validar token JWT usando la función validar_token
-        if let Some(auth_header) = req.headers().get("Authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                if auth_str.starts_with("Bearer ") {
-                    let token = &auth_str[7..];
-                    
-                    match validar_token(token) {
-                        Ok(claims) => {
-                            req.extensions_mut().insert(claims);
-                            let fut = self.service.call(req);
-                            return Box::pin(async move {
-                                let res = fut.await?;
-                                Ok(res)
-                            });
-                        }
-                        Err(e) => {
-                            tracing::warn!("Token inválido: {}", e);
-                        }
-                    }
-                }
-            }
-        }
+        // Extraer y validar token JWT usando la función validar_token
+        let claims = req
+            .headers()
+            .get("Authorization")
+            .and_then(|valor| valor.to_str().ok())
+            .and_then(|valor| valor.strip_prefix("Bearer "))
+            .and_then(|token| {
+                validar_token(token)
+                    .inspect_err(|e| tracing::warn!("Token inválido: {}", e))
+                    .ok()
+            });
+        let service = self.service.clone();
 
-        // Retornar no autorizado usando ApiError
         Box::pin(async move {
-            let api_error = ApiError::unauthorized("Token inválido o faltante".to_string());
-            Err(api_error.into())
+            let Some(claims) = claims else {
+                let api_error = ApiError::unauthorized(Clave::TokenInvalido);
+                return Ok(req.into_response(api_error.error_response()).map_into_right_body());
+            };
+
+            // El token sigue siendo válido tras eliminar o deshabilitar la cuenta
+            let usuario = match req.app_data::<Data<UserService>>() {
+                Some(usuarios) => usuarios.usuario_del_token(&claims).await,
+                None => Err(ApiError::interno("UserService no está registrado en la aplicación")),
+            };
+            let usuario = match usuario {
+                Ok(usuario) => usuario,
+                Err(api_error) => {
+                    return Ok(req.into_response(api_error.error_response()).map_into_right_body());
+                }
+            };
+
+            req.extensions_mut().insert(claims);
+            req.extensions_mut().insert(usuario.clone());
+            let res = match IdiomaSolicitud::para_usuario(&req, usuario.locale.as_deref()) {
+                Some(idioma) => con_idioma(idioma, service.call(req)).await?,
+                None => service.call(req).await?,
+            };
+            Ok(res.map_into_left_body())
         })
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,31 @@
 use actix_cors::Cors;
 
-pub fn cors_config() -> Cors {
-    Cors::default()
-        .allow_any_origin()           // Cualquier origen en desarrollo
-        .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS", "PATCH"])
-        .allow_any_header()           // Cualquier header
-        .supports_credentials()       // Permitir credenciales
-        .max_age(3600)               // Cache de 1 hora
-}
\ No newline at end of file
+use crate::config::cors::CorsSettings;
+
+pub fn cors_config(ajustes: &CorsSettings) -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(ajustes.metodos.iter().map(String::as_str))
+        .expose_headers(ajustes.cabeceras_expuestas.iter().map(String::as_str))
+        .max_age(ajustes.max_age);
+
+    cors = if ajustes.cualquier_origen {
+        cors.allow_any_origin()
+    } else {
+        // Lista de orígenes, con comodines de subdominio
+        let ajustes = ajustes.clone();
+        cors.allowed_origin_fn(move |origen, _| {
+            origen.to_str().is_ok_and(|origen| ajustes.origen_permitido(origen))
+        })
+    };
+
+    cors = match &ajustes.cabeceras {
+        Some(cabeceras) => cors.allowed_headers(cabeceras.iter().map(String::as_str)),
+        None => cors.allow_any_header(),
+    };
+
+    if ajustes.credenciales {
+        cors = cors.supports_credentials();
+    }
+
+    cors
+}
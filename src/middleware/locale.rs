@@ -0,0 +1,108 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderValue},
+    Error, HttpMessage,
+};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::i18n::{con_idioma, Idioma};
+
+/// Elige el idioma de los mensajes de la solicitud: el de `Accept-Language`, el
+/// preferido del usuario del token o el idioma por defecto. Responde con
+/// `Content-Language`.
+///
+/// El preferido del usuario lo aplica `Authentication`, que ya lo tiene cargado
+/// (ver `IdiomaSolicitud`).
+pub struct Localization;
+
+/// Idioma de la solicitud, guardado en sus extensiones.
+#[derive(Debug, Clone, Copy)]
+pub struct IdiomaSolicitud {
+    pub idioma: Idioma,
+    /// `true` si lo pidió el cliente con `Accept-Language`.
+    pub explicito: bool,
+}
+
+impl IdiomaSolicitud {
+    /// Idioma con el que seguir la solicitud del usuario autenticado: su
+    /// preferido, salvo que el cliente pidiera otro. Lo deja anotado para
+    /// `Content-Language`.
+    pub fn para_usuario(req: &ServiceRequest, locale: Option<&str>) -> Option<Idioma> {
+        let mut extensiones = req.extensions_mut();
+        let actual = extensiones.get_mut::<IdiomaSolicitud>()?;
+        if actual.explicito {
+            return None;
+        }
+
+        actual.idioma = locale.and_then(Idioma::desde_codigo)?;
+        Some(actual.idioma)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Localization
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = LocalizationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocalizationMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct LocalizationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocalizationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let solicitado = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|valor| valor.to_str().ok())
+            .and_then(Idioma::desde_accept_language);
+        let idioma = solicitado.unwrap_or_else(Idioma::por_defecto);
+        req.extensions_mut().insert(IdiomaSolicitud { idioma, explicito: solicitado.is_some() });
+
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            // La llamada al servicio interno también corre con el idioma elegido
+            let mut res = con_idioma(idioma, async move { service.call(req).await }).await?;
+
+            // Puede haber cambiado por el idioma preferido del usuario
+            let idioma = res
+                .request()
+                .extensions()
+                .get::<IdiomaSolicitud>()
+                .map_or(idioma, |solicitud| solicitud.idioma);
+            res.headers_mut()
+                .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(idioma.codigo()));
+            res.headers_mut()
+                .append(header::VARY, HeaderValue::from_static("Accept-Language"));
+            Ok(res)
+        })
+    }
+}
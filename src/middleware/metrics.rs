@@ -0,0 +1,95 @@
+use actix_web::{dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use crate::utils::metrics::METRICAS;
+
+// Etiqueta para solicitudes que no coinciden con ninguna ruta, para no crear
+// una serie por cada path desconocido
+const RUTA_DESCONOCIDA: &str = "unmatched";
+
+pub struct Metrics;
+
+impl<S, B> Transform<S, ServiceRequest> for Metrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = MetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MetricsMiddleware { service }))
+    }
+}
+
+pub struct MetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for MetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Etiquetar por patrón (`/api/usuarios/{id}`), no por path real
+        let ruta = req.match_pattern().unwrap_or_else(|| RUTA_DESCONOCIDA.to_string());
+        let metodo = req.method().to_string();
+        let inicio = Instant::now();
+
+        let en_curso = EnCurso::iniciar();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let resultado = fut.await;
+            drop(en_curso);
+
+            let estado = match &resultado {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            let etiquetas = [metodo.as_str(), ruta.as_str(), estado.as_str()];
+
+            METRICAS.http_solicitudes.with_label_values(&etiquetas).inc();
+            METRICAS
+                .http_duracion
+                .with_label_values(&etiquetas)
+                .observe(inicio.elapsed().as_secs_f64());
+
+            resultado
+        })
+    }
+}
+
+// Cuenta una solicitud en `http_en_curso` mientras viva. Va dentro del future:
+// si el cliente se desconecta y el future se descarta, el contador baja igual
+struct EnCurso;
+
+impl EnCurso {
+    fn iniciar() -> Self {
+        METRICAS.http_en_curso.inc();
+        Self
+    }
+}
+
+impl Drop for EnCurso {
+    fn drop(&mut self) {
+        METRICAS.http_en_curso.dec();
+    }
+}
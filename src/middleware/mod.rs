@@ -1,2 +1,7 @@
+pub mod api_version;
 pub mod auth;
-pub mod cors;
\ No newline at end of file
+pub mod cors;
+pub mod locale;
+pub mod metrics;
+pub mod request_id;
+pub mod security_headers;
\ No newline at end of file
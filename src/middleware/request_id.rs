@@ -0,0 +1,146 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tracing::Instrument;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Longitud máxima aceptada para un X-Request-Id recibido del cliente
+const LONGITUD_MAXIMA: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID_ACTUAL: String;
+}
+
+/// Id de la solicitud que se está procesando, si la hay.
+pub fn request_id_actual() -> Option<String> {
+    REQUEST_ID_ACTUAL.try_with(|id| id.clone()).ok()
+}
+
+pub struct RequestIdentifier;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdentifier
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdentifierMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdentifierMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestIdentifierMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdentifierMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Reutilizar el id del cliente o del proxy si es válido; si no, generar uno
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|valor| valor.to_str().ok())
+            .filter(|valor| es_valido(valor))
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        let span = tracing::info_span!(
+            "http",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.path(),
+            otel.kind = "server",
+        );
+        // Continuar la traza del cliente si envía `traceparent`
+        #[cfg(feature = "otel")]
+        crate::utils::telemetry::extraer_contexto(&span, req.headers());
+        #[cfg(feature = "otel")]
+        let span_respuesta = span.clone();
+
+        let cabecera = HeaderValue::from_str(&request_id).ok();
+        let inicio = Instant::now();
+        let service = self.service.clone();
+
+        let tarea = async move {
+            // La llamada al servicio interno ya corre dentro del scope y del span:
+            // lo que los middlewares hacen en su `call` también lleva el request id
+            let resultado = service.call(req).await;
+
+            let estado = match &resultado {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            let estado = estado.as_u16();
+            let latencia_ms = inicio.elapsed().as_secs_f64() * 1000.0;
+            if estado >= 500 {
+                tracing::error!(status = estado, latency_ms = latencia_ms, "Solicitud completada");
+            } else {
+                tracing::info!(status = estado, latency_ms = latencia_ms, "Solicitud completada");
+            }
+
+            match resultado {
+                Ok(mut res) => {
+                    if let Some(valor) = cabecera {
+                        res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), valor);
+                    }
+
+                    #[cfg(feature = "otel")]
+                    crate::utils::telemetry::inyectar_contexto(&span_respuesta, res.headers_mut());
+
+                    Ok(res)
+                }
+                // Sin la solicitud no se puede construir un `ServiceResponse`: el
+                // error se devuelve con su respuesta ya generada, con la cabecera
+                Err(e) => {
+                    let mut respuesta = e.error_response();
+                    if let Some(valor) = cabecera {
+                        respuesta.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), valor);
+                    }
+
+                    #[cfg(feature = "otel")]
+                    crate::utils::telemetry::inyectar_contexto(&span_respuesta, respuesta.headers_mut());
+
+                    Err(InternalError::from_response(e, respuesta).into())
+                }
+            }
+        };
+
+        Box::pin(REQUEST_ID_ACTUAL.scope(request_id, tarea.instrument(span)))
+    }
+}
+
+fn es_valido(valor: &str) -> bool {
+    !valor.is_empty()
+        && valor.len() <= LONGITUD_MAXIMA
+        && valor
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
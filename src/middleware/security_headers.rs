@@ -0,0 +1,77 @@
+use actix_web::{dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::config::security_headers::SecurityHeadersSettings;
+
+/// Añade las cabeceras de seguridad a cada respuesta. Las cabeceras que ya
+/// puso el handler no se reemplazan.
+pub struct SecurityHeaders {
+    ajustes: Rc<SecurityHeadersSettings>,
+}
+
+impl SecurityHeaders {
+    pub fn new(ajustes: SecurityHeadersSettings) -> Self {
+        Self { ajustes: Rc::new(ajustes) }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SecurityHeaders
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = SecurityHeadersMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SecurityHeadersMiddleware {
+            service,
+            ajustes: self.ajustes.clone(),
+        }))
+    }
+}
+
+pub struct SecurityHeadersMiddleware<S> {
+    service: S,
+    ajustes: Rc<SecurityHeadersSettings>,
+}
+
+impl<S, B> Service<ServiceRequest> for SecurityHeadersMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let cabeceras = self.ajustes.para_ruta(req.path());
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+
+            let destino = res.headers_mut();
+            for (nombre, valor) in cabeceras {
+                if !destino.contains_key(&nombre) {
+                    destino.insert(nombre, valor);
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLogs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLogs::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLogs::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLogs::ActorId).integer().null())
+                    .col(ColumnDef::new(AuditLogs::TargetId).integer().null())
+                    .col(ColumnDef::new(AuditLogs::Ip).string().null())
+                    .col(ColumnDef::new(AuditLogs::UserAgent).string().null())
+                    .col(ColumnDef::new(AuditLogs::Changes).json().null())
+                    .col(ColumnDef::new(AuditLogs::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        for (nombre, columna) in [
+            ("idx_audit_logs_created_at", AuditLogs::CreatedAt),
+            ("idx_audit_logs_actor_id", AuditLogs::ActorId),
+            ("idx_audit_logs_target_id", AuditLogs::TargetId),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(nombre)
+                        .table(AuditLogs::Table)
+                        .col(columna)
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLogs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditLogs {
+    Table,
+    Id,
+    Action,
+    ActorId,
+    TargetId,
+    Ip,
+    UserAgent,
+    Changes,
+    CreatedAt,
+}
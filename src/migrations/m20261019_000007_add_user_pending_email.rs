@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_column("users", "pending_email").await? {
+            return Ok(());
+        }
+
+        // Una columna por sentencia: SQLite no admite varias en el mismo ALTER TABLE
+        for columna in [
+            ColumnDef::new(Users::PendingEmail).string().null().to_owned(),
+            ColumnDef::new(Users::EmailToken).string_len(64).null().to_owned(),
+            ColumnDef::new(Users::EmailTokenExpiresAt).timestamp_with_time_zone().null().to_owned(),
+        ] {
+            manager
+                .alter_table(Table::alter().table(Users::Table).add_column(columna).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for columna in [Users::PendingEmail, Users::EmailToken, Users::EmailTokenExpiresAt] {
+            manager
+                .alter_table(Table::alter().table(Users::Table).drop_column(columna).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    PendingEmail,
+    EmailToken,
+    EmailTokenExpiresAt,
+}
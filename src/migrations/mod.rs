@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+mod m20261018_000001_create_users;
+mod m20261018_000002_add_user_soft_delete;
+mod m20261018_000003_create_audit_logs;
+mod m20261018_000004_add_user_version;
+mod m20261019_000005_add_user_disabled;
+mod m20261019_000006_add_user_locale;
+mod m20261019_000007_add_user_pending_email;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20261018_000001_create_users::Migration),
+            Box::new(m20261018_000002_add_user_soft_delete::Migration),
+            Box::new(m20261018_000003_create_audit_logs::Migration),
+            Box::new(m20261018_000004_add_user_version::Migration),
+            Box::new(m20261019_000005_add_user_disabled::Migration),
+            Box::new(m20261019_000006_add_user_locale::Migration),
+            Box::new(m20261019_000007_add_user_pending_email::Migration),
+        ]
+    }
+}
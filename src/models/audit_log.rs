@@ -0,0 +1,75 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+// Acciones registradas en la auditoría
+pub const LOGIN_SUCCESS: &str = "login_success";
+pub const LOGIN_FAILURE: &str = "login_failure";
+pub const USER_REGISTERED: &str = "user_registered";
+pub const USER_UPDATED: &str = "user_updated";
+pub const PASSWORD_CHANGED: &str = "password_changed";
+pub const USER_DELETED: &str = "user_deleted";
+pub const USER_RESTORED: &str = "user_restored";
+pub const USER_PURGED: &str = "user_purged";
+pub const USER_DISABLED: &str = "user_disabled";
+pub const USER_ENABLED: &str = "user_enabled";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_logs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i64,
+    pub action: String,
+    #[serde(alias = "actorId")]
+    pub actor_id: Option<i32>,
+    pub target_id: Option<i32>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub changes: Option<Json>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // La auditoría es de solo inserción
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            Ok(self)
+        } else {
+            Err(DbErr::Custom("Los registros de auditoría no se pueden modificar".to_string()))
+        }
+    }
+
+    async fn before_delete<C>(self, _db: &C) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Err(DbErr::Custom("Los registros de auditoría no se pueden eliminar".to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Página, desde 1
+    pub pagina: Option<u64>,
+    /// Eventos por página (20 por defecto, 100 como máximo)
+    #[serde(alias = "porPagina")]
+    pub por_pagina: Option<u64>,
+    /// Acción, p. ej. `login_failure`
+    pub accion: Option<String>,
+    #[serde(alias = "actorId")]
+    pub actor_id: Option<i32>,
+    #[serde(alias = "objetivoId")]
+    pub objetivo_id: Option<i32>,
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub desde: Option<DateTimeUtc>,
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub hasta: Option<DateTimeUtc>,
+}
@@ -1,17 +1,47 @@
 use sea_orm::entity::prelude::*;
 use sea_orm::Set;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub const ROLE_USER: &str = "user";
+pub const ROLE_ADMIN: &str = "admin";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "users")]
+#[schema(as = User)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub name: String,
     pub email: String,
+    // El hash nunca sale en las respuestas
+    #[serde(skip_serializing)]
+    #[schema(ignore)]
     pub password: String,
+    pub role: String,
+    #[schema(value_type = String, format = DateTime)]
     pub created_at: DateTimeUtc,
+    #[schema(value_type = String, format = DateTime)]
     pub updated_at: DateTimeUtc,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub deleted_at: Option<DateTimeUtc>,
+    pub version: i32,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub disabled_at: Option<DateTimeUtc>,
+    // Idioma de los mensajes cuando la solicitud no envía `Accept-Language`
+    pub locale: Option<String>,
+    // Email nuevo a la espera de confirmación; `email` no cambia hasta entonces.
+    // Solo lo ve su dueño, en `/api/perfil`
+    #[serde(skip_serializing)]
+    #[schema(ignore)]
+    pub pending_email: Option<String>,
+    // Hash del token de confirmación y su caducidad
+    #[serde(skip_serializing)]
+    #[schema(ignore)]
+    pub email_token: Option<String>,
+    #[serde(skip_serializing)]
+    #[schema(ignore)]
+    pub email_token_expires_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,6 +50,8 @@ pub enum Relation {}
 impl ActiveModelBehavior for ActiveModel {
     fn new() -> Self {
         Self {
+            role: Set(ROLE_USER.to_string()),
+            version: Set(1),
             created_at: Set(chrono::Utc::now()),
             updated_at: Set(chrono::Utc::now()),
             ..ActiveModelTrait::default()
@@ -27,23 +59,107 @@ impl ActiveModelBehavior for ActiveModel {
     }
 }
 
-// DTOs para la API
-#[derive(Debug, Deserialize)]
+// `Entity::find()` y `Entity::find_by_id()` excluyen los usuarios eliminados:
+// estos métodos tapan los de `EntityTrait`. Solo la restauración, la purga y
+// las herramientas de administración deben ver los eliminados, y lo piden
+// explícitamente con `with_deleted()` o `find_by_id_with_deleted()`.
+impl Entity {
+    pub fn find() -> Select<Entity> {
+        Self::with_deleted().filter(Column::DeletedAt.is_null())
+    }
+
+    pub fn find_by_id(id: i32) -> Select<Entity> {
+        Self::find_by_id_with_deleted(id).filter(Column::DeletedAt.is_null())
+    }
+
+    pub fn with_deleted() -> Select<Entity> {
+        <Self as EntityTrait>::find()
+    }
+
+    pub fn find_by_id_with_deleted(id: i32) -> Select<Entity> {
+        <Self as EntityTrait>::find_by_id(id)
+    }
+}
+
+impl Model {
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_ADMIN
+    }
+
+    // Un usuario deshabilitado conserva sus datos pero no puede iniciar sesión
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+}
+
+// DTOs para la API. Los campos se llaman en inglés, como en las respuestas;
+// los nombres en español (`nombre`, `correo`, `contrasena`, `idioma`) se siguen
+// aceptando en la entrada mientras los clientes migran. Los de varias palabras
+// también se aceptan en camelCase (`currentPassword`), como los devuelve la v2
+// con `API_FIELD_CASE=camelCase`.
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateUserDto {
+    #[serde(alias = "nombre")]
     pub name: String,
+    #[serde(alias = "correo")]
     pub email: String,
+    #[serde(alias = "contrasena", alias = "contraseña")]
     pub password: String,
+    /// Idioma preferido: `es` o `en`.
+    #[serde(default, alias = "idioma")]
+    pub locale: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Campos a cambiar; los ausentes se mantienen.
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateUserDto {
+    #[serde(alias = "nombre")]
     pub name: Option<String>,
+    #[serde(alias = "correo")]
     pub email: Option<String>,
+    #[serde(alias = "contrasena", alias = "contraseña")]
     pub password: Option<String>,
+    #[serde(alias = "idioma")]
+    pub locale: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginDto {
+    #[serde(alias = "correo")]
     pub email: String,
+    #[serde(alias = "contrasena", alias = "contraseña")]
     pub password: String,
-}
\ No newline at end of file
+}
+
+/// Cambios del propio perfil. Un email distinto queda pendiente hasta que se
+/// confirme con el código enviado.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateProfileDto {
+    #[serde(alias = "nombre")]
+    pub name: Option<String>,
+    #[serde(alias = "correo")]
+    pub email: Option<String>,
+    #[serde(alias = "idioma")]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmEmailDto {
+    #[serde(alias = "codigo", alias = "código")]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangePasswordDto {
+    #[serde(alias = "currentPassword", alias = "contrasena_actual", alias = "contraseña_actual")]
+    pub current_password: String,
+    #[serde(alias = "newPassword", alias = "contrasena_nueva", alias = "contraseña_nueva")]
+    pub new_password: String,
+}
+
+/// La contraseña actual confirma el borrado de la cuenta.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteAccountDto {
+    #[serde(alias = "contrasena", alias = "contraseña")]
+    pub password: String,
+}
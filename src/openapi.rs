@@ -0,0 +1,102 @@
+use std::sync::LazyLock;
+
+use utoipa::openapi::path::{Operation, PathItem};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::controllers::{
+    audit_controller, auth_controller, docs_controller, health_controller, metrics_controller,
+    user_controller,
+};
+use crate::responses::api_response::{Enlaces, Meta, Paginacion};
+
+/// Documento OpenAPI 3.1 generado a partir de las anotaciones de los handlers.
+/// Cada ruta de las tablas `RUTAS` de `routes::*` debe aparecer aquí (lo comprueba un test).
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "API Rust con Actix-web",
+        description = "Una API REST simple construida con Rust y el framework Actix-web.\n\n\
+            Los cuerpos documentados son los de la v1. Desde la v2 (`/api/v2` o \
+            `Accept: application/vnd.api+json;version=2`) las respuestas correctas se envuelven \
+            en `{ data, meta, links }`, con `meta` y `links` según los esquemas `Meta` y `Enlaces`."
+    ),
+    paths(
+        health_controller::health_check,
+        health_controller::liveness,
+        health_controller::readiness,
+        health_controller::api_info,
+        auth_controller::login,
+        user_controller::create_user,
+        user_controller::perfil,
+        user_controller::update_profile,
+        user_controller::confirm_email,
+        user_controller::change_password,
+        user_controller::delete_profile,
+        user_controller::get_users,
+        user_controller::get_user,
+        user_controller::update_user,
+        user_controller::patch_user,
+        user_controller::delete_user,
+        user_controller::restore_user,
+        audit_controller::get_audit_logs,
+        metrics_controller::metrics,
+        docs_controller::openapi_json,
+        docs_controller::swagger_ui,
+        docs_controller::swagger_initializer,
+        docs_controller::swagger_archivo,
+    ),
+    components(schemas(Meta, Enlaces, Paginacion)),
+    modifiers(&SeguridadJwt),
+    tags(
+        (name = "salud", description = "Estado del servidor y sondas"),
+        (name = "auth", description = "Registro e inicio de sesión"),
+        (name = "perfil", description = "Datos y cuenta del usuario autenticado"),
+        (name = "usuarios", description = "Gestión de usuarios"),
+        (name = "auditoría", description = "Registro de auditoría"),
+        (name = "métricas", description = "Métricas de Prometheus"),
+        (name = "documentación", description = "Este documento y su visor"),
+    )
+)]
+pub struct ApiDoc;
+
+pub static DOCUMENTO: LazyLock<utoipa::openapi::OpenApi> = LazyLock::new(|| {
+    let mut documento = ApiDoc::openapi();
+    // utoipa toma la licencia de Cargo.toml, que no declara ninguna
+    documento.info.license = None;
+    documento
+});
+
+// Las rutas protegidas usan `security(("bearer" = []))`
+struct SeguridadJwt;
+
+impl Modify for SeguridadJwt {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let componentes = openapi.components.get_or_insert_with(Default::default);
+        componentes.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Operaciones de una ruta con su método en mayúsculas.
+pub fn operaciones(ruta: &PathItem) -> impl Iterator<Item = (&'static str, &Operation)> {
+    [
+        ("GET", &ruta.get),
+        ("POST", &ruta.post),
+        ("PUT", &ruta.put),
+        ("PATCH", &ruta.patch),
+        ("DELETE", &ruta.delete),
+        ("HEAD", &ruta.head),
+        ("OPTIONS", &ruta.options),
+        ("TRACE", &ruta.trace),
+    ]
+    .into_iter()
+    .filter_map(|(metodo, operacion)| operacion.as_ref().map(|operacion| (metodo, operacion)))
+}
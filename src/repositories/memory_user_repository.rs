@@ -0,0 +1,88 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::DbErr;
+
+use super::user_repository::{NuevoUsuario, UserRepository};
+use crate::models::user::Model as UserModel;
+
+/// Usuarios en memoria, para tests sin base de datos.
+#[derive(Debug, Default)]
+pub struct InMemoryUserRepository {
+    usuarios: Mutex<Vec<UserModel>>,
+}
+
+impl InMemoryUserRepository {
+    // Un mutex envenenado solo indica que otro test falló a mitad de una operación
+    fn usuarios(&self) -> std::sync::MutexGuard<'_, Vec<UserModel>> {
+        self.usuarios.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn listar(&self) -> Result<Vec<UserModel>, DbErr> {
+        Ok(self.usuarios().iter().filter(|u| u.deleted_at.is_none()).cloned().collect())
+    }
+
+    async fn obtener(&self, id: i32) -> Result<Option<UserModel>, DbErr> {
+        self.buscar(id, false).await
+    }
+
+    async fn buscar(&self, id: i32, incluir_eliminados: bool) -> Result<Option<UserModel>, DbErr> {
+        Ok(self
+            .usuarios()
+            .iter()
+            .find(|u| u.id == id && (incluir_eliminados || u.deleted_at.is_none()))
+            .cloned())
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<UserModel>, DbErr> {
+        Ok(self
+            .usuarios()
+            .iter()
+            .find(|u| u.email == email && u.deleted_at.is_none())
+            .cloned())
+    }
+
+    async fn crear(&self, usuario: NuevoUsuario, ahora: DateTime<Utc>) -> Result<UserModel, DbErr> {
+        let mut usuarios = self.usuarios();
+        let usuario = UserModel {
+            id: usuarios.last().map_or(1, |u| u.id + 1),
+            name: usuario.name,
+            email: usuario.email,
+            password: usuario.password,
+            role: usuario.role,
+            created_at: ahora,
+            updated_at: ahora,
+            deleted_at: None,
+            version: 1,
+            disabled_at: None,
+            locale: usuario.locale,
+            pending_email: None,
+            email_token: None,
+            email_token_expires_at: None,
+        };
+
+        usuarios.push(usuario.clone());
+        Ok(usuario)
+    }
+
+    async fn actualizar(&self, usuario: UserModel, version_leida: i32) -> Result<Option<UserModel>, DbErr> {
+        let mut usuarios = self.usuarios();
+        let Some(actual) = usuarios.iter_mut().find(|u| u.id == usuario.id) else {
+            return Ok(None);
+        };
+        if actual.version != version_leida {
+            return Ok(None);
+        }
+
+        *actual = UserModel {
+            created_at: actual.created_at,
+            version: version_leida + 1,
+            ..usuario
+        };
+        Ok(Some(actual.clone()))
+    }
+}
@@ -0,0 +1,7 @@
+pub mod memory_user_repository;
+pub mod sea_orm_user_repository;
+pub mod user_repository;
+
+pub use memory_user_repository::InMemoryUserRepository;
+pub use sea_orm_user_repository::SeaOrmUserRepository;
+pub use user_repository::{NuevoUsuario, UserRepository};
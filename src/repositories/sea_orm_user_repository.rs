@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, Set,
+};
+
+use super::user_repository::{NuevoUsuario, UserRepository};
+use crate::config::database::ReadReplica;
+use crate::models::user::{self, Column, Entity as UserEntity, Model as UserModel};
+
+/// Usuarios en la base de datos; las lecturas van a la réplica.
+pub struct SeaOrmUserRepository {
+    db: DatabaseConnection,
+    replica: ReadReplica,
+}
+
+impl SeaOrmUserRepository {
+    pub fn new(db: DatabaseConnection, replica: ReadReplica) -> Self {
+        Self { db, replica }
+    }
+}
+
+#[async_trait]
+impl UserRepository for SeaOrmUserRepository {
+    async fn listar(&self) -> Result<Vec<UserModel>, DbErr> {
+        UserEntity::find()
+            .order_by_asc(Column::Id)
+            .all(self.replica.conexion())
+            .await
+    }
+
+    async fn obtener(&self, id: i32) -> Result<Option<UserModel>, DbErr> {
+        UserEntity::find_by_id(id).one(self.replica.conexion()).await
+    }
+
+    async fn buscar(&self, id: i32, incluir_eliminados: bool) -> Result<Option<UserModel>, DbErr> {
+        let consulta = if incluir_eliminados {
+            UserEntity::find_by_id_with_deleted(id)
+        } else {
+            UserEntity::find_by_id(id)
+        };
+        consulta.one(&self.db).await
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<UserModel>, DbErr> {
+        UserEntity::find()
+            .filter(Column::Email.eq(email))
+            .one(&self.db)
+            .await
+    }
+
+    async fn crear(&self, usuario: NuevoUsuario, ahora: DateTime<Utc>) -> Result<UserModel, DbErr> {
+        user::ActiveModel {
+            name: Set(usuario.name),
+            email: Set(usuario.email),
+            password: Set(usuario.password),
+            role: Set(usuario.role),
+            locale: Set(usuario.locale),
+            version: Set(1),
+            created_at: Set(ahora),
+            updated_at: Set(ahora),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    async fn actualizar(&self, usuario: UserModel, version_leida: i32) -> Result<Option<UserModel>, DbErr> {
+        let id = usuario.id;
+        let mut activo = usuario.into_active_model().reset_all();
+        activo.id = sea_orm::ActiveValue::NotSet;
+        activo.created_at = sea_orm::ActiveValue::NotSet;
+        activo.version = Set(version_leida + 1);
+
+        // El filtro por versión hace la comprobación y la escritura atómicas
+        let resultado = UserEntity::update_many()
+            .set(activo)
+            .filter(Column::Id.eq(id))
+            .filter(Column::Version.eq(version_leida))
+            .exec(&self.db)
+            .await?;
+
+        if resultado.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        // También al eliminar: se devuelve el usuario ya marcado como eliminado
+        UserEntity::find_by_id_with_deleted(id).one(&self.db).await
+    }
+}
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::DbErr;
+
+use crate::models::user::Model as UserModel;
+
+/// Datos de un usuario nuevo; la contraseña ya viene cifrada.
+#[derive(Debug, Clone)]
+pub struct NuevoUsuario {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    pub role: String,
+    pub locale: Option<String>,
+}
+
+/// Almacenamiento de usuarios. Solo persiste: las reglas de negocio
+/// (email único, cifrado, validaciones) están en `UserService`.
+///
+/// `listar` y `obtener` atienden lecturas y pueden usar la réplica; el resto
+/// se usa antes de escribir y debe leer de la primaria.
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// Usuarios no eliminados, ordenados por id.
+    async fn listar(&self) -> Result<Vec<UserModel>, DbErr>;
+
+    /// Usuario no eliminado por id.
+    async fn obtener(&self, id: i32) -> Result<Option<UserModel>, DbErr>;
+
+    /// Usuario por id; los eliminados solo con `incluir_eliminados`.
+    async fn buscar(&self, id: i32, incluir_eliminados: bool) -> Result<Option<UserModel>, DbErr>;
+
+    /// Usuario no eliminado por email.
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<UserModel>, DbErr>;
+
+    async fn crear(&self, usuario: NuevoUsuario, ahora: DateTime<Utc>) -> Result<UserModel, DbErr>;
+
+    /// Guarda `usuario` solo si su versión sigue siendo `version_leida` e
+    /// incrementa la versión. Devuelve `None` si otra escritura se adelantó.
+    async fn actualizar(&self, usuario: UserModel, version_leida: i32) -> Result<Option<UserModel>, DbErr>;
+}
@@ -0,0 +1,187 @@
+use actix_web::body::BoxBody;
+use actix_web::http::header::{HeaderName, HeaderValue, TryIntoHeaderPair};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::i18n::Mensaje;
+use crate::middleware::api_version::FormatoRespuesta;
+use crate::middleware::request_id::request_id_actual;
+
+// Parámetro de la consulta con el número de página
+const PARAMETRO_PAGINA: &str = "pagina";
+
+/// Respuesta correcta de un handler.
+///
+/// Desde la v2 se envía como `{ data, meta, links }`, con los nombres de campo
+/// de `API_FIELD_CASE`; en la v1 (modo de compatibilidad) se envía el cuerpo
+/// anterior del endpoint: `legado` si se indicó, o los datos sin envolver.
+pub struct ApiResponse<T> {
+    datos: T,
+    estado: StatusCode,
+    mensaje: Option<Mensaje>,
+    paginacion: Option<Paginacion>,
+    legado: Option<Value>,
+    cabeceras: Vec<(HeaderName, HeaderValue)>,
+}
+
+/// Información de la respuesta que no forma parte del recurso.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Meta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub api_version: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub message: Option<Mensaje>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Paginacion>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Paginacion {
+    #[serde(rename = "page")]
+    pub pagina: u64,
+    #[serde(rename = "per_page")]
+    pub por_pagina: u64,
+    pub total: u64,
+    #[serde(rename = "total_pages")]
+    pub total_paginas: u64,
+}
+
+/// Enlaces de la respuesta; en las listas paginadas, también los de navegación.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Enlaces {
+    #[serde(rename = "self")]
+    pub actual: String,
+    #[serde(rename = "first", skip_serializing_if = "Option::is_none")]
+    pub primera: Option<String>,
+    #[serde(rename = "prev", skip_serializing_if = "Option::is_none")]
+    pub anterior: Option<String>,
+    #[serde(rename = "next", skip_serializing_if = "Option::is_none")]
+    pub siguiente: Option<String>,
+    #[serde(rename = "last", skip_serializing_if = "Option::is_none")]
+    pub ultima: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Envoltorio<T> {
+    data: T,
+    meta: Meta,
+    links: Enlaces,
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    pub fn new(datos: T) -> Self {
+        Self {
+            datos,
+            estado: StatusCode::OK,
+            mensaje: None,
+            paginacion: None,
+            legado: None,
+            cabeceras: Vec::new(),
+        }
+    }
+
+    pub fn estado(mut self, estado: StatusCode) -> Self {
+        self.estado = estado;
+        self
+    }
+
+    pub fn mensaje(mut self, mensaje: impl Into<Mensaje>) -> Self {
+        self.mensaje = Some(mensaje.into());
+        self
+    }
+
+    pub fn paginacion(mut self, paginacion: Paginacion) -> Self {
+        self.paginacion = Some(paginacion);
+        self
+    }
+
+    /// Cuerpo que recibe la v1 en lugar de los datos sin envolver.
+    pub fn legado(mut self, cuerpo: Value) -> Self {
+        self.legado = Some(cuerpo);
+        self
+    }
+
+    pub fn cabecera(mut self, cabecera: impl TryIntoHeaderPair) -> Self {
+        match cabecera.try_into_pair() {
+            Ok(par) => self.cabeceras.push(par),
+            Err(_) => tracing::warn!("Cabecera de respuesta inválida"),
+        }
+        self
+    }
+}
+
+impl<T: Serialize> Responder for ApiResponse<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse {
+        let mut respuesta = HttpResponse::build(self.estado);
+        for cabecera in self.cabeceras {
+            respuesta.insert_header(cabecera);
+        }
+
+        let formato = FormatoRespuesta::actual();
+        if !formato.envuelto() {
+            return match self.legado {
+                Some(cuerpo) => respuesta.json(cuerpo),
+                None => respuesta.json(self.datos),
+            };
+        }
+
+        let links = enlaces(req, self.paginacion.as_ref());
+        let envoltorio = Envoltorio {
+            data: self.datos,
+            meta: Meta {
+                request_id: request_id_actual(),
+                api_version: formato.version,
+                message: self.mensaje,
+                pagination: self.paginacion,
+            },
+            links,
+        };
+
+        match formato.campos.aplicar(&envoltorio) {
+            Ok(cuerpo) => respuesta.json(cuerpo),
+            Err(e) => HttpResponse::from_error(crate::errors::api_error::ApiError::interno(e)),
+        }
+    }
+}
+
+fn enlaces(req: &HttpRequest, paginacion: Option<&Paginacion>) -> Enlaces {
+    let uri = req.uri();
+    let actual = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str()).to_string();
+
+    let Some(paginacion) = paginacion else {
+        return Enlaces { actual, primera: None, anterior: None, siguiente: None, ultima: None };
+    };
+
+    // El resto de filtros de la consulta se conservan en cada enlace
+    let filtros: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|par| !par.is_empty() && par.split('=').next() != Some(PARAMETRO_PAGINA))
+        .collect();
+    let pagina = |numero: u64| {
+        let consulta = filtros
+            .iter()
+            .copied()
+            .chain(std::iter::once(format!("{}={}", PARAMETRO_PAGINA, numero).as_str()))
+            .collect::<Vec<_>>()
+            .join("&");
+        format!("{}?{}", uri.path(), consulta)
+    };
+
+    let ultima = paginacion.total_paginas.max(1);
+    Enlaces {
+        actual,
+        primera: Some(pagina(1)),
+        anterior: (paginacion.pagina > 1).then(|| pagina((paginacion.pagina - 1).min(ultima))),
+        siguiente: (paginacion.pagina < ultima).then(|| pagina(paginacion.pagina + 1)),
+        ultima: Some(pagina(ultima)),
+    }
+}
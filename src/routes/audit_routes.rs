@@ -0,0 +1,11 @@
+use actix_web::web;
+use crate::controllers::audit_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/auditoria" => audit_controller::get_audit_logs),
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    registrar(cfg, RUTAS);
+}
@@ -1,11 +1,13 @@
 use actix_web::web;
 use crate::controllers::auth_controller;
 use crate::controllers::user_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(post "/auth/login" => auth_controller::login),
+    ruta!(post "/auth/registro" => user_controller::create_user),
+];
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api/auth")
-            .route("/login", web::post().to(auth_controller::login))
-            .route("/registro", web::post().to(user_controller::create_user))
-    );
-}
\ No newline at end of file
+    registrar(cfg, RUTAS);
+}
@@ -1,11 +1,57 @@
 use actix_web::web;
+use crate::config::api_version::VERSIONES;
+use super::ruta::{registrar, Ruta};
 use super::user_routes;
 use super::auth_routes;
 use super::health_routes;
+use super::audit_routes;
+use super::docs_routes;
+use super::info_routes;
+use super::metrics_routes;
+use super::profile_routes;
+
+// Rutas con su ruta completa. `/metrics` se registra aparte (ver `AppConfig`)
+const RUTAS_RAIZ: [&[Ruta]; 2] = [health_routes::RUTAS, docs_routes::RUTAS];
+
+// Rutas relativas a `/api/v{n}`
+const RUTAS_API: [&[Ruta]; 5] = [
+    info_routes::RUTAS,
+    auth_routes::RUTAS,
+    profile_routes::RUTAS,
+    user_routes::RUTAS,
+    audit_routes::RUTAS,
+];
 
 pub fn config_routes(cfg: &mut web::ServiceConfig) {
-    cfg
-        .configure(health_routes::config)
-        .configure(auth_routes::config)
-        .configure(user_routes::config);
-}
\ No newline at end of file
+    for rutas in RUTAS_RAIZ {
+        registrar(cfg, rutas);
+    }
+
+    // Cada versión monta las mismas rutas bajo `/api/v{n}`; las rutas sin versión
+    // llegan aquí reescritas por el middleware `ApiVersioning`
+    for version in VERSIONES {
+        cfg.service(web::scope(&format!("/api/v{}", version)).configure(api_routes));
+    }
+}
+
+fn api_routes(cfg: &mut web::ServiceConfig) {
+    for rutas in RUTAS_API {
+        registrar(cfg, rutas);
+    }
+}
+
+/// Todas las rutas de la aplicación como `("GET", "/api/usuarios/{id}")`, con
+/// la ruta con la que se documentan: las de cada versión bajo `/api`.
+pub fn rutas() -> Vec<(String, String)> {
+    let raiz = RUTAS_RAIZ
+        .into_iter()
+        .chain([metrics_routes::RUTAS])
+        .flatten()
+        .map(|ruta| (ruta.metodo.to_uppercase(), ruta.patron.to_string()));
+    let api = RUTAS_API
+        .into_iter()
+        .flatten()
+        .map(|ruta| (ruta.metodo.to_uppercase(), format!("/api{}", ruta.patron)));
+
+    raiz.chain(api).collect()
+}
@@ -0,0 +1,14 @@
+use actix_web::web;
+use crate::controllers::docs_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/api/openapi.json" => docs_controller::openapi_json),
+    ruta!(get "/api/docs" => docs_controller::swagger_ui),
+    ruta!(get "/api/docs/swagger-initializer.js" => docs_controller::swagger_initializer),
+    ruta!(get "/api/docs/{archivo}" => docs_controller::swagger_archivo),
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    registrar(cfg, RUTAS);
+}
@@ -1,8 +1,13 @@
-use crate::controllers::{health_controller, user_controller};
+use crate::controllers::health_controller;
 use actix_web::web;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/api/salud" => health_controller::health_check),
+    ruta!(get "/api/salud/vivo" => health_controller::liveness),
+    ruta!(get "/api/salud/listo" => health_controller::readiness),
+];
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.route("/api/salud", web::get().to(health_controller::health_check))
-        .route("/api/perfil", web::get().to(user_controller::perfil))
-        .route("/api/info", web::get().to(health_controller::api_info));
+    registrar(cfg, RUTAS);
 }
@@ -0,0 +1,11 @@
+use actix_web::web;
+use crate::controllers::health_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/info" => health_controller::api_info),
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    registrar(cfg, RUTAS);
+}
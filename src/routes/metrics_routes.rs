@@ -0,0 +1,11 @@
+use actix_web::web;
+use crate::controllers::metrics_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/metrics" => metrics_controller::metrics),
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    registrar(cfg, RUTAS);
+}
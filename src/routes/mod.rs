@@ -1,4 +1,10 @@
 pub mod config;
+pub mod ruta;
 pub mod user_routes;
 pub mod auth_routes;
-pub mod health_routes;
\ No newline at end of file
+pub mod health_routes;
+pub mod audit_routes;
+pub mod metrics_routes;
+pub mod docs_routes;
+pub mod info_routes;
+pub mod profile_routes;
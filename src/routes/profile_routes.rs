@@ -0,0 +1,15 @@
+use actix_web::web;
+use crate::controllers::user_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/perfil" => user_controller::perfil),
+    ruta!(patch "/perfil" => user_controller::update_profile),
+    ruta!(delete "/perfil" => user_controller::delete_profile),
+    ruta!(post "/perfil/contrasena" => user_controller::change_password),
+    ruta!(post "/perfil/email/confirmar" => user_controller::confirm_email),
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    registrar(cfg, RUTAS);
+}
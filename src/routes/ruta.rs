@@ -0,0 +1,42 @@
+use actix_web::{web, Resource};
+
+/// Una ruta de la tabla `RUTAS` de un módulo: método, patrón y cómo añadirla
+/// a su recurso. La misma tabla registra las rutas en actix y es la lista con
+/// la que se comprueba el documento OpenAPI.
+pub struct Ruta {
+    /// Método en minúsculas, como la función de `web` (`get`, `post`...).
+    pub metodo: &'static str,
+    pub patron: &'static str,
+    pub agregar: fn(Resource) -> Resource,
+}
+
+/// `ruta!(get "/usuarios" => user_controller::get_users)`
+macro_rules! ruta {
+    ($metodo:ident $patron:literal => $handler:path) => {
+        $crate::routes::ruta::Ruta {
+            metodo: stringify!($metodo),
+            patron: $patron,
+            agregar: |recurso| recurso.route(actix_web::web::$metodo().to($handler)),
+        }
+    };
+}
+pub(crate) use ruta;
+
+/// Registra las rutas de la tabla en orden. Las que comparten patrón van en un
+/// mismo recurso, así un método no registrado responde 405.
+pub fn registrar(cfg: &mut web::ServiceConfig, rutas: &[Ruta]) {
+    let mut patrones: Vec<&str> = Vec::new();
+    for ruta in rutas {
+        if !patrones.contains(&ruta.patron) {
+            patrones.push(ruta.patron);
+        }
+    }
+
+    for patron in patrones {
+        let recurso = rutas
+            .iter()
+            .filter(|ruta| ruta.patron == patron)
+            .fold(web::resource(patron), |recurso, ruta| (ruta.agregar)(recurso));
+        cfg.service(recurso);
+    }
+}
@@ -1,12 +1,16 @@
 use actix_web::web;
 use crate::controllers::user_controller;
+use super::ruta::{registrar, ruta, Ruta};
+
+pub const RUTAS: &[Ruta] = &[
+    ruta!(get "/usuarios" => user_controller::get_users),
+    ruta!(get "/usuarios/{id}" => user_controller::get_user),
+    ruta!(put "/usuarios/{id}" => user_controller::update_user),
+    ruta!(patch "/usuarios/{id}" => user_controller::patch_user),
+    ruta!(delete "/usuarios/{id}" => user_controller::delete_user),
+    ruta!(post "/usuarios/{id}/restaurar" => user_controller::restore_user),
+];
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api/usuarios")
-            .route("", web::get().to(user_controller::get_users))
-            .route("/{id}", web::get().to(user_controller::get_user))
-            .route("/{id}", web::put().to(user_controller::update_user))
-            .route("/{id}", web::delete().to(user_controller::delete_user))
-    );
-}
\ No newline at end of file
+    registrar(cfg, RUTAS);
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::config::env::var_opcional;
+use crate::config::environment::Environment;
+
+/// Correo listo para enviar.
+#[derive(Debug, Clone)]
+pub struct Correo {
+    pub para: String,
+    pub asunto: String,
+    pub cuerpo: String,
+}
+
+/// Envío de correos. El cuerpo puede llevar secretos (códigos de
+/// confirmación): solo `LogEmailSender`, que no se usa en producción, lo
+/// escribe en el log.
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn enviar(&self, correo: Correo) -> io::Result<()>;
+}
+
+/// Envío configurado por el entorno: con `EMAIL_OUTBOX_DIR` los correos se
+/// guardan como archivos en ese directorio; sin él, en desarrollo solo se
+/// anotan en el log. En producción sin configurar no hay envío y las
+/// operaciones que lo necesitan fallan.
+pub fn email_sender_from_env() -> Option<Arc<dyn EmailSender>> {
+    if let Some(directorio) = var_opcional("EMAIL_OUTBOX_DIR") {
+        return Some(Arc::new(FileEmailSender::new(directorio)));
+    }
+
+    if Environment::from_env().is_production() {
+        tracing::warn!("⚠️ Sin envío de correo configurado (EMAIL_OUTBOX_DIR): no se pueden confirmar emails");
+        None
+    } else {
+        Some(Arc::new(LogEmailSender))
+    }
+}
+
+impl Correo {
+    // `para` y `asunto` van a cabeceras: un salto de línea añadiría cabeceras nuevas
+    fn comprobar_cabeceras(&self) -> io::Result<()> {
+        if [&self.para, &self.asunto].iter().any(|valor| valor.contains(['\r', '\n'])) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "salto de línea en una cabecera del correo"));
+        }
+        Ok(())
+    }
+}
+
+/// Anota el destinatario y el asunto; el cuerpo, con el código de
+/// confirmación, solo a nivel debug. Solo para desarrollo.
+#[derive(Debug, Default)]
+pub struct LogEmailSender;
+
+#[async_trait]
+impl EmailSender for LogEmailSender {
+    async fn enviar(&self, correo: Correo) -> io::Result<()> {
+        correo.comprobar_cabeceras()?;
+        tracing::info!(para = %correo.para, asunto = %correo.asunto, "Correo no enviado (sin EMAIL_OUTBOX_DIR)");
+        tracing::debug!(para = %correo.para, cuerpo = %correo.cuerpo, "Cuerpo del correo no enviado");
+        Ok(())
+    }
+}
+
+/// Escribe cada correo en un archivo del directorio, para recogerlo con otra
+/// herramienta o leerlo en desarrollo.
+#[derive(Debug)]
+pub struct FileEmailSender {
+    directorio: PathBuf,
+}
+
+impl FileEmailSender {
+    pub fn new(directorio: impl Into<PathBuf>) -> Self {
+        Self { directorio: directorio.into() }
+    }
+}
+
+#[async_trait]
+impl EmailSender for FileEmailSender {
+    async fn enviar(&self, correo: Correo) -> io::Result<()> {
+        correo.comprobar_cabeceras()?;
+        fs::create_dir_all(&self.directorio)?;
+        let ruta = self
+            .directorio
+            .join(format!("{}-{}.eml", Utc::now().format("%Y%m%d%H%M%S%f"), uuid::Uuid::new_v4()));
+        let contenido = format!(
+            "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            correo.para, correo.asunto, correo.cuerpo
+        );
+
+        fs::write(&ruta, contenido)?;
+        tracing::info!(para = %correo.para, archivo = %ruta.display(), "Correo guardado");
+        Ok(())
+    }
+}
+
+/// Correos enviados en memoria, para tests.
+#[derive(Debug, Default)]
+pub struct InMemoryEmailSender {
+    enviados: Mutex<Vec<Correo>>,
+}
+
+impl InMemoryEmailSender {
+    pub fn enviados(&self) -> Vec<Correo> {
+        self.enviados.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[async_trait]
+impl EmailSender for InMemoryEmailSender {
+    async fn enviar(&self, correo: Correo) -> io::Result<()> {
+        correo.comprobar_cabeceras()?;
+        self.enviados.lock().unwrap_or_else(|e| e.into_inner()).push(correo);
+        Ok(())
+    }
+}
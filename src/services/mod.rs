@@ -0,0 +1,5 @@
+pub mod email_sender;
+pub mod user_service;
+
+pub use email_sender::{email_sender_from_env, Correo, EmailSender, FileEmailSender, InMemoryEmailSender, LogEmailSender};
+pub use user_service::{Autenticacion, UserService};
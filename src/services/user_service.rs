@@ -0,0 +1,488 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use sea_orm::DbErr;
+use serde_json::Value;
+
+use crate::errors::api_error::{ApiError, ErrorCampo};
+use crate::i18n::{Clave, Idioma, Mensaje};
+use crate::models::user::{CreateUserDto, Model as UserModel, UpdateProfileDto, UpdateUserDto};
+use crate::repositories::{NuevoUsuario, UserRepository};
+use crate::services::email_sender::{Correo, EmailSender};
+use crate::utils::email::{es_email_valido, generar_token, hash_token};
+use crate::utils::hash::{hash_password, verify_password};
+use crate::utils::jwt::Claims;
+use crate::utils::password_policy::PasswordPolicy;
+use crate::utils::patch;
+
+// Campos que un PATCH puede modificar; el resto se rechaza
+const CAMPOS_EDITABLES: [&str; 3] = ["name", "email", "locale"];
+
+// Campos del usuario que no se serializan: no están en el documento al que se
+// aplica el parche, pero existen y no se pueden modificar
+const CAMPOS_OCULTOS: [&str; 4] = ["password", "pending_email", "email_token", "email_token_expires_at"];
+
+// Tiempo para confirmar un email nuevo desde el perfil
+const VIGENCIA_CONFIRMACION_EMAIL: Duration = Duration::hours(24);
+
+/// Resultado de comprobar unas credenciales.
+#[derive(Debug)]
+pub enum Autenticacion {
+    Valida(UserModel),
+    /// Contraseña correcta, pero la cuenta está deshabilitada.
+    Deshabilitada(UserModel),
+    /// Email desconocido o contraseña incorrecta; incluye el id si el email existe.
+    Invalida(Option<i32>),
+}
+
+/// Reglas de negocio de los usuarios: email único, política y cifrado de
+/// contraseñas, validación de cambios y control de versiones. No conoce HTTP;
+/// la auditoría la registran los handlers.
+#[derive(Clone)]
+pub struct UserService {
+    repositorio: Arc<dyn UserRepository>,
+    // Sin envío de correo no se pueden confirmar emails nuevos
+    correo: Option<Arc<dyn EmailSender>>,
+}
+
+impl UserService {
+    pub fn new(repositorio: impl UserRepository + 'static) -> Self {
+        Self { repositorio: Arc::new(repositorio), correo: None }
+    }
+
+    /// Envío de los códigos de confirmación de email.
+    pub fn con_correo(mut self, correo: Arc<dyn EmailSender>) -> Self {
+        self.correo = Some(correo);
+        self
+    }
+
+    pub async fn listar(&self) -> Result<Vec<UserModel>, ApiError> {
+        self.repositorio.listar().await.map_err(error_db)
+    }
+
+    /// Usuario no eliminado para responder una lectura (puede venir de la réplica).
+    pub async fn obtener(&self, id: i32) -> Result<UserModel, ApiError> {
+        self.repositorio.obtener(id).await.map_err(error_db)?.ok_or_else(no_encontrado)
+    }
+
+    /// Usuario no eliminado leído de la primaria, antes de modificarlo.
+    pub async fn buscar(&self, id: i32) -> Result<UserModel, ApiError> {
+        self.repositorio.buscar(id, false).await.map_err(error_db)?.ok_or_else(no_encontrado)
+    }
+
+    pub async fn registrar(&self, datos: &CreateUserDto, rol: &str) -> Result<UserModel, ApiError> {
+        validar_email(&datos.email)?;
+        validar_contraseña(&datos.password, &datos.name, &datos.email, "password")?;
+        let locale = validar_idioma(datos.locale.as_deref())?;
+        self.comprobar_email_libre(&datos.email, None).await?;
+
+        let usuario = NuevoUsuario {
+            name: datos.name.clone(),
+            email: datos.email.clone(),
+            password: cifrar(&datos.password)?,
+            role: rol.to_string(),
+            locale,
+        };
+
+        self.repositorio.crear(usuario, Utc::now()).await.map_err(error_db)
+    }
+
+    pub async fn autenticar(&self, email: &str, contraseña: &str) -> Result<Autenticacion, ApiError> {
+        let Some(usuario) = self.repositorio.buscar_por_email(email).await.map_err(error_db)? else {
+            return Ok(Autenticacion::Invalida(None));
+        };
+
+        let valida = verify_password(contraseña, &usuario.password)
+            .map_err(ApiError::interno)?;
+
+        Ok(match valida {
+            false => Autenticacion::Invalida(Some(usuario.id)),
+            true if usuario.is_disabled() => Autenticacion::Deshabilitada(usuario),
+            true => Autenticacion::Valida(usuario),
+        })
+    }
+
+    /// Aplica los campos presentes en `cambios` (semántica de `PUT` parcial).
+    /// Solo un administrador cambia el email así; el usuario lo cambia desde su
+    /// perfil, con confirmación.
+    pub async fn actualizar(&self, usuario: UserModel, cambios: &UpdateUserDto, por_admin: bool) -> Result<UserModel, ApiError> {
+        if cambios.email.as_ref().is_some_and(|email| *email != usuario.email) && !por_admin {
+            return Err(email_solo_desde_perfil());
+        }
+        if cambios.password.is_some() && !por_admin {
+            return Err(ApiError::validation(vec![ErrorCampo::new("password", Clave::ContraseñaSoloDesdePerfil)]));
+        }
+        if let Some(email) = &cambios.email {
+            validar_email(email)?;
+        }
+        if let Some(password) = &cambios.password {
+            // Validar contra los datos finales del usuario, no contra los anteriores
+            let nombre = cambios.name.as_deref().unwrap_or(&usuario.name);
+            let email = cambios.email.as_deref().unwrap_or(&usuario.email);
+            validar_contraseña(password, nombre, email, "password")?;
+        }
+        let locale = validar_idioma(cambios.locale.as_deref())?;
+        if let Some(email) = &cambios.email {
+            self.comprobar_email_libre(email, Some(usuario.id)).await?;
+        }
+
+        let version = usuario.version;
+        let mut usuario = usuario;
+        if let Some(name) = &cambios.name {
+            usuario.name = name.clone();
+        }
+        if let Some(email) = &cambios.email {
+            if *email != usuario.email {
+                usuario = sin_email_pendiente(usuario);
+            }
+            usuario.email = email.clone();
+        }
+        if let Some(password) = &cambios.password {
+            usuario.password = cifrar(password)?;
+        }
+        if locale.is_some() {
+            usuario.locale = locale;
+        }
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Guarda el resultado de aplicar un parche al JSON del usuario. Solo se
+    /// admiten cambios en los campos editables y el resultado debe ser válido.
+    /// Como en `actualizar`, solo un administrador puede cambiar el email.
+    pub async fn parchear(&self, usuario: UserModel, documento: &Value, por_admin: bool) -> Result<UserModel, ApiError> {
+        let original = serde_json::to_value(&usuario)
+            .map_err(ApiError::interno)?;
+
+        let mut errores: Vec<ErrorCampo> = patch::campos_modificados(&original, documento)
+            .into_iter()
+            .filter(|campo| !CAMPOS_EDITABLES.contains(&campo.as_str()))
+            .map(|campo| {
+                let mensaje = if original.get(&campo).is_some() || CAMPOS_OCULTOS.contains(&campo.as_str()) {
+                    Clave::CampoNoModificable
+                } else {
+                    Clave::CampoDesconocido
+                };
+                ErrorCampo::new(&campo, mensaje)
+            })
+            .collect();
+
+        // Validar el resultado del parche, no el parche en sí
+        let name = documento.get("name").and_then(|v| v.as_str()).unwrap_or_default().trim();
+        let email = documento.get("email").and_then(|v| v.as_str()).unwrap_or_default().trim();
+
+        if name.is_empty() {
+            errores.push(ErrorCampo::new("name", Clave::NombreRequerido));
+        }
+        if !es_email_valido(email) {
+            errores.push(ErrorCampo::new("email", Clave::EmailInvalido));
+        }
+        let locale = match documento.get("locale") {
+            None | Some(Value::Null) => Ok(None),
+            Some(valor) => validar_idioma(Some(valor.as_str().unwrap_or_default())),
+        };
+        if let Err(error) = &locale {
+            errores.extend(error.errores.iter().cloned());
+        }
+        if !errores.is_empty() {
+            return Err(ApiError::validation(errores));
+        }
+        let usuario = if email != usuario.email {
+            if !por_admin {
+                return Err(email_solo_desde_perfil());
+            }
+            self.comprobar_email_libre(email, Some(usuario.id)).await?;
+            sin_email_pendiente(usuario)
+        } else {
+            usuario
+        };
+
+        let version = usuario.version;
+        let usuario = UserModel {
+            name: name.to_string(),
+            email: email.to_string(),
+            locale: locale?,
+            ..usuario
+        };
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Borrado lógico: la purga periódica lo elimina tras el periodo de retención.
+    pub async fn eliminar(&self, usuario: UserModel) -> Result<UserModel, ApiError> {
+        let version = usuario.version;
+        let usuario = UserModel { deleted_at: Some(Utc::now()), ..usuario };
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Cambios del propio perfil: nombre e idioma se aplican ya, pero un email
+    /// distinto queda pendiente hasta confirmarlo con `confirmar_email`. El
+    /// código se envía al email nuevo y solo se guarda su hash.
+    pub async fn actualizar_perfil(&self, usuario: UserModel, cambios: &UpdateProfileDto) -> Result<UserModel, ApiError> {
+        let mut errores = Vec::new();
+        let name = cambios.name.as_deref().map(str::trim);
+        let email = cambios.email.as_deref().map(str::trim);
+
+        if name.is_some_and(str::is_empty) {
+            errores.push(ErrorCampo::new("name", Clave::NombreRequerido));
+        }
+        if email.is_some_and(|email| !es_email_valido(email)) {
+            errores.push(ErrorCampo::new("email", Clave::EmailInvalido));
+        }
+        let locale = validar_idioma(cambios.locale.as_deref());
+        if let Err(error) = &locale {
+            errores.extend(error.errores.iter().cloned());
+        }
+        if !errores.is_empty() {
+            return Err(ApiError::validation(errores));
+        }
+
+        let version = usuario.version;
+        let mut usuario = usuario;
+        let mut envio = None;
+        if let Some(name) = name {
+            usuario.name = name.to_string();
+        }
+        if let Some(locale) = locale? {
+            usuario.locale = Some(locale);
+        }
+        match email {
+            // Volver al email actual cancela el cambio pendiente
+            Some(email) if email == usuario.email => usuario = sin_email_pendiente(usuario),
+            Some(email) => {
+                let correo = self
+                    .correo
+                    .clone()
+                    .ok_or_else(|| ApiError::service_unavailable(Clave::CorreoNoDisponible))?;
+                self.comprobar_email_libre(email, Some(usuario.id)).await?;
+
+                let token = generar_token();
+                usuario.pending_email = Some(email.to_string());
+                usuario.email_token = Some(hash_token(&token));
+                usuario.email_token_expires_at = Some(Utc::now() + VIGENCIA_CONFIRMACION_EMAIL);
+                envio = Some((correo, email.to_string(), token));
+            }
+            None => {}
+        }
+
+        let usuario = self.guardar(usuario, version).await?;
+
+        // Si el envío falla, el usuario puede pedir el cambio otra vez: se genera otro código
+        if let Some((correo, para, token)) = envio {
+            let mensaje = Correo {
+                para,
+                asunto: Mensaje::new(Clave::AsuntoConfirmacionEmail).to_string(),
+                cuerpo: Mensaje::new(Clave::CuerpoConfirmacionEmail).con("codigo", token).to_string(),
+            };
+            correo.enviar(mensaje).await.map_err(|e| {
+                tracing::error!("No se pudo enviar el correo de confirmación: {}", e);
+                ApiError::service_unavailable(Clave::CorreoNoDisponible)
+            })?;
+        }
+
+        Ok(usuario)
+    }
+
+    /// Aplica el email pendiente si el código coincide y no caducó.
+    pub async fn confirmar_email(&self, usuario: UserModel, token: &str) -> Result<UserModel, ApiError> {
+        let valido = usuario.email_token.as_deref() == Some(hash_token(token.trim()).as_str())
+            && usuario.email_token_expires_at.is_some_and(|caduca| caduca > Utc::now());
+        let Some(email) = usuario.pending_email.clone().filter(|_| valido) else {
+            return Err(ApiError::validation(vec![ErrorCampo::new("token", Clave::TokenEmailInvalido)]));
+        };
+        // Otro usuario pudo registrarse con ese email mientras tanto
+        self.comprobar_email_libre(&email, Some(usuario.id)).await?;
+
+        let version = usuario.version;
+        let usuario = UserModel { email, ..sin_email_pendiente(usuario) };
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Cambia la contraseña del propio usuario tras comprobar la actual.
+    pub async fn cambiar_contraseña(&self, usuario: UserModel, actual: &str, nueva: &str) -> Result<UserModel, ApiError> {
+        comprobar_contraseña_actual(&usuario, actual, "current_password")?;
+        validar_contraseña(nueva, &usuario.name, &usuario.email, "new_password")?;
+
+        let version = usuario.version;
+        let usuario = UserModel { password: cifrar(nueva)?, ..usuario };
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Borrado lógico de la propia cuenta, confirmado con la contraseña.
+    pub async fn eliminar_cuenta(&self, usuario: UserModel, contraseña: &str) -> Result<UserModel, ApiError> {
+        comprobar_contraseña_actual(&usuario, contraseña, "password")?;
+
+        self.eliminar(usuario).await
+    }
+
+    pub async fn restaurar(&self, id: i32) -> Result<UserModel, ApiError> {
+        let usuario = match self.repositorio.buscar(id, true).await.map_err(error_db)? {
+            Some(usuario) if usuario.deleted_at.is_some() => usuario,
+            Some(_) => return Err(ApiError::bad_request(Clave::UsuarioNoEliminado)),
+            None => return Err(no_encontrado()),
+        };
+        self.comprobar_email_libre(&usuario.email, Some(usuario.id)).await?;
+
+        let version = usuario.version;
+        let usuario = UserModel { deleted_at: None, ..usuario };
+
+        self.guardar(usuario, version).await
+    }
+
+    /// Usuario de un token ya validado. Los tokens emitidos antes de eliminar o
+    /// deshabilitar la cuenta dejan de servir aunque no hayan caducado.
+    pub async fn usuario_del_token(&self, claims: &Claims) -> Result<UserModel, ApiError> {
+        let id = claims
+            .usuario_id()
+            .ok_or_else(|| ApiError::unauthorized(Clave::TokenInvalido))?;
+
+        let usuario = self
+            .repositorio
+            .obtener(id)
+            .await
+            .map_err(error_db)?
+            .ok_or_else(|| ApiError::unauthorized(Clave::TokenInvalido))?;
+
+        comprobar_habilitado(usuario)
+    }
+
+    /// Carga el usuario autenticado y comprueba que tenga rol de administrador.
+    pub async fn requerir_admin(&self, claims: &Claims) -> Result<UserModel, ApiError> {
+        let id = claims
+            .usuario_id()
+            .ok_or_else(|| ApiError::bad_request(Clave::IdUsuarioInvalido))?;
+
+        let usuario = self
+            .repositorio
+            .buscar(id, false)
+            .await
+            .map_err(error_db)?
+            .ok_or_else(|| ApiError::unauthorized(Clave::TokenInvalido))?;
+        // Se vuelve a comprobar en la primaria: la réplica puede ir por detrás
+        let usuario = comprobar_habilitado(usuario)?;
+
+        if !usuario.is_admin() {
+            return Err(ApiError::forbidden(Clave::SeRequiereAdmin));
+        }
+
+        Ok(usuario)
+    }
+
+    /// Un usuario solo puede modificar o eliminar su propio registro; un
+    /// administrador, cualquiera.
+    pub fn comprobar_acceso(&self, actor: &UserModel, id: i32) -> Result<(), ApiError> {
+        if actor.is_admin() || actor.id == id {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden(Clave::SoloPropioUsuario))
+        }
+    }
+
+    // Guarda los cambios solo si nadie modificó el usuario desde que se leyó
+    // (`version` sin cambios); si no, responde 412.
+    async fn guardar(&self, usuario: UserModel, version_leida: i32) -> Result<UserModel, ApiError> {
+        let usuario = UserModel { updated_at: Utc::now(), ..usuario };
+
+        self.repositorio
+            .actualizar(usuario, version_leida)
+            .await
+            .map_err(error_db)?
+            .ok_or_else(|| ApiError::precondition_failed(Clave::RecursoModificado))
+    }
+
+    // El email es único entre los usuarios no eliminados
+    async fn comprobar_email_libre(&self, email: &str, excepto: Option<i32>) -> Result<(), ApiError> {
+        let existente = self.repositorio.buscar_por_email(email).await.map_err(error_db)?;
+
+        match existente {
+            Some(usuario) if Some(usuario.id) != excepto => Err(ApiError::conflict(Clave::EmailEnUso)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Los errores se asignan al campo de la solicitud (`password` o `new_password`)
+fn validar_contraseña(contraseña: &str, nombre: &str, email: &str, campo: &str) -> Result<(), ApiError> {
+    let errores: Vec<ErrorCampo> = PasswordPolicy::from_env()
+        .validar(contraseña, nombre, email)
+        .into_iter()
+        .map(|error| ErrorCampo { campo: campo.to_string(), ..error })
+        .collect();
+    if errores.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::validation(errores))
+    }
+}
+
+fn validar_email(email: &str) -> Result<(), ApiError> {
+    if es_email_valido(email) {
+        Ok(())
+    } else {
+        Err(ApiError::validation(vec![ErrorCampo::new("email", Clave::EmailInvalido)]))
+    }
+}
+
+// Normaliza el idioma preferido a su código (`en-US` → `en`)
+fn validar_idioma(locale: Option<&str>) -> Result<Option<String>, ApiError> {
+    let Some(locale) = locale else {
+        return Ok(None);
+    };
+
+    match Idioma::desde_codigo(locale) {
+        Some(idioma) => Ok(Some(idioma.codigo().to_string())),
+        None => {
+            let disponibles = Idioma::TODOS.map(Idioma::codigo).join(", ");
+            Err(ApiError::validation(vec![ErrorCampo::new(
+                "locale",
+                Mensaje::new(Clave::IdiomaNoSoportado).con("disponibles", disponibles),
+            )]))
+        }
+    }
+}
+
+fn sin_email_pendiente(usuario: UserModel) -> UserModel {
+    UserModel {
+        pending_email: None,
+        email_token: None,
+        email_token_expires_at: None,
+        ..usuario
+    }
+}
+
+fn email_solo_desde_perfil() -> ApiError {
+    ApiError::validation(vec![ErrorCampo::new("email", Clave::EmailSoloDesdePerfil)])
+}
+
+fn comprobar_habilitado(usuario: UserModel) -> Result<UserModel, ApiError> {
+    if usuario.is_disabled() {
+        Err(ApiError::forbidden(Clave::CuentaDeshabilitada))
+    } else {
+        Ok(usuario)
+    }
+}
+
+// La contraseña incorrecta es un error del campo, no de autenticación: el
+// token sigue siendo válido
+fn comprobar_contraseña_actual(usuario: &UserModel, contraseña: &str, campo: &str) -> Result<(), ApiError> {
+    if verify_password(contraseña, &usuario.password).map_err(ApiError::interno)? {
+        Ok(())
+    } else {
+        Err(ApiError::validation(vec![ErrorCampo::new(campo, Clave::ContraseñaActualIncorrecta)]))
+    }
+}
+
+fn cifrar(contraseña: &str) -> Result<String, ApiError> {
+    hash_password(contraseña).map_err(ApiError::interno)
+}
+
+fn no_encontrado() -> ApiError {
+    ApiError::not_found(Clave::UsuarioNoEncontrado)
+}
+
+fn error_db(e: DbErr) -> ApiError {
+    ApiError::interno(e)
+}
@@ -0,0 +1 @@
+pub mod user_purge;
\ No newline at end of file
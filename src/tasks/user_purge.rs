@@ -0,0 +1,71 @@
+use chrono::{Duration, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::env::var_o;
+use crate::models::audit_log;
+use crate::models::user::{Column, Entity as UserEntity};
+use crate::utils::audit::{self, AuditEvent};
+
+/// Lanza la tarea que elimina definitivamente los usuarios borrados hace más
+/// de `USER_RETENTION_DAYS` días (30 por defecto). Se ejecuta cada
+/// `USER_PURGE_INTERVAL_SECS` segundos (una hora por defecto) hasta que se
+/// cancela `cancelacion`; una purga en curso termina antes de salir.
+pub fn iniciar(db: DatabaseConnection, cancelacion: CancellationToken) -> JoinHandle<()> {
+    let dias_retencion: i64 = var_o("USER_RETENTION_DAYS", 30);
+    let mut intervalo_segundos: u64 = var_o("USER_PURGE_INTERVAL_SECS", 3600);
+    // `tokio::time::interval` no admite un periodo de cero
+    if intervalo_segundos == 0 {
+        tracing::warn!("⚠️ USER_PURGE_INTERVAL_SECS=0 no es válido; se usa 1 segundo");
+        intervalo_segundos = 1;
+    }
+
+    tokio::spawn(async move {
+        let mut intervalo = tokio::time::interval(std::time::Duration::from_secs(intervalo_segundos));
+
+        loop {
+            tokio::select! {
+                _ = cancelacion.cancelled() => break,
+                _ = intervalo.tick() => {}
+            }
+
+            match purgar(&db, dias_retencion).await {
+                Ok(0) => {}
+                Ok(eliminados) => tracing::info!("Purga de usuarios: {} eliminados definitivamente", eliminados),
+                Err(e) => tracing::error!("Error al purgar usuarios eliminados: {}", e),
+            }
+        }
+
+        tracing::info!("Tarea de purga de usuarios detenida");
+    })
+}
+
+/// Elimina definitivamente los usuarios borrados antes del periodo de
+/// retención y deja un evento de auditoría por cada uno.
+pub async fn purgar(db: &DatabaseConnection, dias_retencion: i64) -> Result<u64, DbErr> {
+    let limite = Utc::now() - Duration::days(dias_retencion);
+
+    let ids: Vec<i32> = UserEntity::with_deleted()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::DeletedAt.lt(limite))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let resultado = UserEntity::delete_many()
+        .filter(Column::Id.is_in(ids.clone()))
+        .filter(Column::DeletedAt.lt(limite))
+        .exec(db)
+        .await?;
+
+    for id in ids {
+        audit::registrar_desde_tarea(db, AuditEvent::new(audit_log::USER_PURGED).target(id)).await;
+    }
+
+    Ok(resultado.rows_affected)
+}
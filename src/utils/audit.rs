@@ -0,0 +1,129 @@
+use std::sync::LazyLock;
+
+use actix_web::HttpRequest;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use serde_json::{json, Map, Value};
+
+use crate::config::proxies::TrustedProxies;
+use crate::models::audit_log::ActiveModel as AuditLogActiveModel;
+use crate::models::user::Model as UserModel;
+
+/// Datos de un evento de auditoría; la IP y el user agent se toman de la solicitud.
+pub struct AuditEvent {
+    pub accion: &'static str,
+    pub actor_id: Option<i32>,
+    pub target_id: Option<i32>,
+    pub cambios: Option<Value>,
+}
+
+impl AuditEvent {
+    pub fn new(accion: &'static str) -> Self {
+        Self { accion, actor_id: None, target_id: None, cambios: None }
+    }
+
+    pub fn actor(mut self, actor_id: impl Into<Option<i32>>) -> Self {
+        self.actor_id = actor_id.into();
+        self
+    }
+
+    pub fn target(mut self, target_id: impl Into<Option<i32>>) -> Self {
+        self.target_id = target_id.into();
+        self
+    }
+
+    pub fn cambios(mut self, cambios: Value) -> Self {
+        self.cambios = Some(cambios);
+        self
+    }
+}
+
+static PROXIES: LazyLock<TrustedProxies> = LazyLock::new(TrustedProxies::from_env);
+
+/// Guarda el evento en `audit_logs`. Un fallo se registra en el log pero no
+/// interrumpe la operación que lo originó.
+pub async fn registrar(db: &DatabaseConnection, req: &HttpRequest, evento: AuditEvent) {
+    // La IP de la conexión; las cabeceras de reenvío solo si viene de un proxy de confianza
+    let ip = req.peer_addr().map(|conexion| {
+        let x_forwarded_for = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|valor| valor.to_str().ok());
+        PROXIES.ip_cliente(conexion.ip(), x_forwarded_for).to_string()
+    });
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|valor| valor.to_str().ok())
+        .map(str::to_string);
+
+    guardar(db, evento, ip, user_agent).await;
+}
+
+// User agent con el que se registran las acciones de la CLI de administración
+const USER_AGENT_CLI: &str = concat!("rust-api-cli/", env!("CARGO_PKG_VERSION"));
+
+/// Guarda un evento originado en la CLI de administración (sin IP).
+pub async fn registrar_desde_cli(db: &DatabaseConnection, evento: AuditEvent) {
+    guardar(db, evento, None, Some(USER_AGENT_CLI.to_string())).await;
+}
+
+// User agent con el que se registran las acciones de las tareas en segundo plano
+const USER_AGENT_TAREAS: &str = concat!("rust-api-tareas/", env!("CARGO_PKG_VERSION"));
+
+/// Guarda un evento originado en una tarea en segundo plano (sin actor ni IP).
+pub async fn registrar_desde_tarea(db: &DatabaseConnection, evento: AuditEvent) {
+    guardar(db, evento, None, Some(USER_AGENT_TAREAS.to_string())).await;
+}
+
+async fn guardar(
+    db: &DatabaseConnection,
+    evento: AuditEvent,
+    ip: Option<String>,
+    user_agent: Option<String>,
+) {
+    let registro = AuditLogActiveModel {
+        action: Set(evento.accion.to_string()),
+        actor_id: Set(evento.actor_id),
+        target_id: Set(evento.target_id),
+        ip: Set(ip),
+        user_agent: Set(user_agent),
+        changes: Set(evento.cambios),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    };
+
+    if let Err(e) = registro.insert(db).await {
+        tracing::error!("Error al registrar evento de auditoría '{}': {}", evento.accion, e);
+    }
+}
+
+// Campos que nunca se guardan en la auditoría
+const CAMPOS_SECRETOS: [&str; 1] = ["password"];
+
+// Campos que cambian en cada escritura y no aportan al diff
+const CAMPOS_IGNORADOS: [&str; 2] = ["updated_at", "version"];
+
+/// Diferencia campo a campo entre dos estados del usuario, sin secretos:
+/// `{"name": {"antes": "...", "despues": "..."}}`. `None` si no cambió nada
+/// más que los campos de control, y entonces no hay nada que auditar.
+pub fn diff_usuario(antes: &UserModel, despues: &UserModel) -> Option<Value> {
+    let antes = serde_json::to_value(antes).unwrap_or_default();
+    let despues = serde_json::to_value(despues).unwrap_or_default();
+    let mut cambios = Map::new();
+
+    if let (Value::Object(antes), Value::Object(despues)) = (antes, despues) {
+        for (campo, valor_nuevo) in despues {
+            if CAMPOS_SECRETOS.contains(&campo.as_str()) || CAMPOS_IGNORADOS.contains(&campo.as_str()) {
+                continue;
+            }
+
+            let valor_anterior = antes.get(&campo).cloned().unwrap_or(Value::Null);
+            if valor_anterior != valor_nuevo {
+                cambios.insert(campo, json!({ "antes": valor_anterior, "despues": valor_nuevo }));
+            }
+        }
+    }
+
+    (!cambios.is_empty()).then_some(Value::Object(cambios))
+}
@@ -0,0 +1,45 @@
+use rand::RngCore;
+use sha1::{Digest, Sha1};
+
+/// Comprobación básica de una dirección: una sola `@`, sin espacios ni
+/// caracteres de control (la dirección acaba en cabeceras de correo) y un
+/// dominio con al menos dos etiquetas de letras, dígitos o guiones.
+pub fn es_email_valido(email: &str) -> bool {
+    let Some((local, dominio)) = email.split_once('@') else {
+        return false;
+    };
+    if local.is_empty() || local.len() > 64 || email.len() > 254 {
+        return false;
+    }
+    if email.chars().any(|c| c.is_whitespace() || c.is_control()) || dominio.contains('@') {
+        return false;
+    }
+    if local.chars().any(|c| matches!(c, '<' | '>' | '(' | ')' | '[' | ']' | ',' | ';' | ':' | '\\' | '"')) {
+        return false;
+    }
+
+    let etiquetas: Vec<&str> = dominio.split('.').collect();
+    etiquetas.len() >= 2
+        && etiquetas.iter().all(|etiqueta| {
+            !etiqueta.is_empty()
+                && etiqueta.len() <= 63
+                && !etiqueta.starts_with('-')
+                && !etiqueta.ends_with('-')
+                && etiqueta.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+/// Código aleatorio para confirmar un email nuevo. Solo se guarda su hash
+/// (`hash_token`), así que no se puede recuperar de la base de datos.
+pub fn generar_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn hash_token(token: &str) -> String {
+    Sha1::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
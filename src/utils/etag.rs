@@ -0,0 +1,45 @@
+use actix_web::http::header::{EntityTag, Header, IfMatch, IfNoneMatch};
+use actix_web::HttpRequest;
+
+use crate::config::env::var_bool;
+use crate::errors::api_error::ApiError;
+use crate::i18n::Clave;
+use crate::models::user::Model as UserModel;
+
+/// ETag fuerte de un usuario, derivado de su id y su versión.
+pub fn etag_usuario(usuario: &UserModel) -> EntityTag {
+    EntityTag::new_strong(format!("{}-{}", usuario.id, usuario.version))
+}
+
+/// `true` si el cliente ya tiene la versión actual (`If-None-Match`) y se
+/// puede responder `304 Not Modified`.
+pub fn no_modificado(req: &HttpRequest, etag: &EntityTag) -> bool {
+    match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(etags)) => etags.iter().any(|e| e.weak_eq(etag)),
+        Err(_) => false,
+    }
+}
+
+/// Comprueba `If-Match` antes de modificar un recurso. Si `REQUIRE_IF_MATCH`
+/// está activo, la cabecera es obligatoria (`428`); si no coincide, `412`.
+pub fn verificar_if_match(req: &HttpRequest, etag: &EntityTag) -> Result<(), ApiError> {
+    if !req.headers().contains_key(actix_web::http::header::IF_MATCH) {
+        if var_bool("REQUIRE_IF_MATCH", false) {
+            return Err(ApiError::precondition_required(Clave::IfMatchRequerido));
+        }
+        return Ok(());
+    }
+
+    let coincide = match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => true,
+        Ok(IfMatch::Items(etags)) => etags.iter().any(|e| e.strong_eq(etag)),
+        Err(_) => false,
+    };
+
+    if coincide {
+        Ok(())
+    } else {
+        Err(ApiError::precondition_failed(Clave::RecursoModificado))
+    }
+}
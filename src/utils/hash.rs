@@ -1,14 +1,27 @@
 use bcrypt::{hash, verify, DEFAULT_COST};
+use std::time::Instant;
 
+use crate::utils::metrics::METRICAS;
+
+#[tracing::instrument(name = "password.hash", skip_all)]
 pub fn hash_password(contraseña: &str) -> Result<String, bcrypt::BcryptError> {
     let cost: u32 = std::env::var("BCRYPT_COST")
         .unwrap_or_else(|_| DEFAULT_COST.to_string())
         .parse()
         .unwrap_or(DEFAULT_COST);
     
-    hash(contraseña, cost)
+    let inicio = Instant::now();
+    let resultado = hash(contraseña, cost);
+    METRICAS.hash_duracion.with_label_values(&["hash"]).observe(inicio.elapsed().as_secs_f64());
+
+    resultado
 }
 
+#[tracing::instrument(name = "password.verify", skip_all)]
 pub fn verify_password(contraseña: &str, hasheada: &str) -> Result<bool, bcrypt::BcryptError> {
-    verify(contraseña, hasheada)
+    let inicio = Instant::now();
+    let resultado = verify(contraseña, hasheada);
+    METRICAS.hash_duracion.with_label_values(&["verify"]).observe(inicio.elapsed().as_secs_f64());
+
+    resultado
 }
\ No newline at end of file
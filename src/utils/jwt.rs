@@ -1,6 +1,15 @@
-use jsonwebtoken::{encode, decode, Header, Validation, Algorithm, EncodingKey, DecodingKey};
+use jsonwebtoken::{encode, decode, decode_header, Header, Validation, Algorithm, EncodingKey, DecodingKey};
+use jsonwebtoken::errors::ErrorKind;
 use serde::{Deserialize, Serialize};
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
+use rand::RngCore;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::config::env::var_opcional;
 
 #[derive(Debug, Serialize, Deserialize,Clone)]
 pub struct Claims {
@@ -8,12 +17,39 @@ pub struct Claims {
     pub exp: usize,
 }
 
+impl Claims {
+    pub fn usuario_id(&self) -> Option<i32> {
+        self.sub.parse().ok()
+    }
+}
+
+/// Claves de firma guardadas en `JWT_KEYS_FILE`. Los tokens se firman con la
+/// activa y llevan su `kid`; las anteriores siguen validando los tokens ya emitidos.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JwtKeySet {
+    pub activa: String,
+    pub claves: Vec<JwtKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtKey {
+    pub kid: String,
+    pub secret: String,
+    pub creada: DateTime<Utc>,
+}
+
+impl JwtKeySet {
+    fn buscar(&self, kid: &str) -> Option<&JwtKey> {
+        self.claves.iter().find(|clave| clave.kid == kid)
+    }
+}
+
 pub fn generar_token(id_usuario: String) -> Result<String, jsonwebtoken::errors::Error> {
     let expiracion_horas: u64 = std::env::var("JWT_EXPIRATION_HOURS")
         .unwrap_or_else(|_| "24".to_string())
         .parse()
         .unwrap_or(24);
-    
+
     let expiracion = Utc::now()
         .checked_add_signed(Duration::hours(expiracion_horas as i64))
         .expect("Tiempo de expiración inválido")
@@ -24,9 +60,25 @@ pub fn generar_token(id_usuario: String) -> Result<String, jsonwebtoken::errors:
         exp: expiracion,
     };
 
-    let secret = std::env::var("JWT_SECRET")
-        .expect("❌ JWT_SECRET debe estar configurada en el archivo .env");
-    
+    // Con JWT_KEYS_FILE se firma con la clave activa y se indica su `kid`
+    if let Some(claves) = claves_configuradas() {
+        let clave = claves
+            .buscar(&claves.activa)
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidKeyFormat))?;
+        let header = Header {
+            kid: Some(clave.kid.clone()),
+            ..Header::default()
+        };
+
+        return encode(&header, &claims, &EncodingKey::from_secret(clave.secret.as_ref()));
+    }
+
+    // Sin clave con la que firmar no se emite el token (el login responde 500)
+    let secret = secreto_sin_kid().ok_or_else(|| {
+        tracing::error!("No hay clave para firmar tokens: configura JWT_SECRET o JWT_KEYS_FILE");
+        jsonwebtoken::errors::Error::from(ErrorKind::InvalidKeyFormat)
+    })?;
+
     encode(
         &Header::default(),
         &claims,
@@ -35,12 +87,105 @@ pub fn generar_token(id_usuario: String) -> Result<String, jsonwebtoken::errors:
 }
 
 pub fn validar_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let secret = std::env::var("JWT_SECRET")
-        .expect("❌ JWT_SECRET debe estar configurada en el archivo .env");
-    
+    let kid = decode_header(token)?.kid;
+
+    // Los tokens sin `kid` se emitieron con JWT_SECRET, antes de usar el archivo de claves
+    let secret = match (kid, claves_configuradas()) {
+        (Some(kid), Some(claves)) => claves
+            .buscar(&kid)
+            .map(|clave| clave.secret.clone())
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidToken))?,
+        (Some(_), None) => return Err(ErrorKind::InvalidToken.into()),
+        // Solo se aceptan mientras JWT_SECRET siga configurada
+        (None, _) => secreto_sin_kid().ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidToken))?,
+    };
+
     decode::<Claims>(
         token,
         &DecodingKey::from_secret(secret.as_ref()),
         &Validation::new(Algorithm::HS256),
     ).map(|data| data.claims)
-}
\ No newline at end of file
+}
+
+/// Genera una clave nueva, la marca como activa y conserva solo las
+/// `conservar` más recientes (incluida la nueva).
+pub fn rotar_claves(ruta: &Path, conservar: usize) -> io::Result<JwtKeySet> {
+    let mut claves = match fs::read_to_string(ruta) {
+        Ok(contenido) => serde_json::from_str(&contenido).map_err(io::Error::other)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => JwtKeySet::default(),
+        Err(e) => return Err(e),
+    };
+
+    let mut secreto = [0u8; 32];
+    rand::rng().fill_bytes(&mut secreto);
+    let ahora = Utc::now();
+    let clave = JwtKey {
+        kid: format!("k{}-{:04x}", ahora.format("%Y%m%d%H%M%S"), rand::rng().next_u32() as u16),
+        secret: secreto.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        creada: ahora,
+    };
+
+    claves.activa = clave.kid.clone();
+    claves.claves.push(clave);
+    let sobrantes = claves.claves.len().saturating_sub(conservar.max(1));
+    claves.claves.drain(..sobrantes);
+
+    guardar_claves(ruta, &claves)?;
+    Ok(claves)
+}
+
+// Escribe en un archivo temporal y lo renombra para que el servidor nunca lea
+// un archivo a medio escribir
+fn guardar_claves(ruta: &Path, claves: &JwtKeySet) -> io::Result<()> {
+    let temporal = ruta.with_extension("tmp");
+    let contenido = serde_json::to_string_pretty(claves).map_err(io::Error::other)?;
+    fs::write(&temporal, contenido)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temporal, fs::Permissions::from_mode(0o600))?;
+    }
+
+    fs::rename(&temporal, ruta)
+}
+
+// Secreto de los tokens sin `kid`. No se recorta: el valor es la clave tal cual
+fn secreto_sin_kid() -> Option<String> {
+    std::env::var("JWT_SECRET").ok().filter(|secreto| !secreto.is_empty())
+}
+
+// Caché del archivo de claves; se vuelve a leer cuando cambia su fecha de modificación
+static CLAVES: RwLock<Option<(SystemTime, Arc<JwtKeySet>)>> = RwLock::new(None);
+
+fn claves_configuradas() -> Option<Arc<JwtKeySet>> {
+    let ruta = var_opcional("JWT_KEYS_FILE")?;
+    let modificado = match fs::metadata(&ruta).and_then(|m| m.modified()) {
+        Ok(modificado) => modificado,
+        Err(e) => {
+            tracing::error!("No se pudo leer JWT_KEYS_FILE ({}): {}", ruta, e);
+            return None;
+        }
+    };
+
+    if let Some((fecha, claves)) = CLAVES.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if *fecha == modificado {
+            return Some(claves.clone());
+        }
+    }
+
+    let claves: JwtKeySet = match fs::read_to_string(&ruta)
+        .map_err(|e| e.to_string())
+        .and_then(|contenido| serde_json::from_str(&contenido).map_err(|e| e.to_string()))
+    {
+        Ok(claves) => claves,
+        Err(e) => {
+            tracing::error!("JWT_KEYS_FILE ({}) es inválido: {}", ruta, e);
+            return None;
+        }
+    };
+
+    let claves = Arc::new(claves);
+    *CLAVES.write().unwrap_or_else(|e| e.into_inner()) = Some((modificado, claves.clone()));
+    Some(claves)
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio_util::sync::CancellationToken;
+
+/// Estado del ciclo de vida del servidor, compartido entre workers.
+#[derive(Debug, Default)]
+pub struct Lifecycle {
+    apagando: AtomicBool,
+    cancelacion: CancellationToken,
+}
+
+impl Lifecycle {
+    /// Marca el servidor como en apagado: la sonda de disponibilidad empieza a fallar.
+    pub fn iniciar_apagado(&self) {
+        self.apagando.store(true, Ordering::SeqCst);
+    }
+
+    pub fn esta_apagando(&self) -> bool {
+        self.apagando.load(Ordering::SeqCst)
+    }
+
+    /// Token que las tareas en segundo plano vigilan para terminar.
+    pub fn token_cancelacion(&self) -> CancellationToken {
+        self.cancelacion.clone()
+    }
+
+    /// Pide a las tareas en segundo plano que terminen.
+    pub fn cancelar_tareas(&self) {
+        self.cancelacion.cancel();
+    }
+}
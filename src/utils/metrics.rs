@@ -0,0 +1,88 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sea_orm::DatabaseConnection;
+
+use crate::config::database::estadisticas_pool;
+
+/// Métricas de la aplicación en un registro propio de Prometheus.
+pub struct Metricas {
+    pub registro: Registry,
+    pub http_solicitudes: IntCounterVec,
+    pub http_duracion: HistogramVec,
+    pub http_en_curso: IntGauge,
+    pub db_pool_conexiones: IntGaugeVec,
+    pub hash_duracion: HistogramVec,
+    pub logins: IntCounterVec,
+}
+
+pub static METRICAS: LazyLock<Metricas> = LazyLock::new(|| {
+    let registro = Registry::new();
+
+    let http_solicitudes = IntCounterVec::new(
+        Opts::new("http_requests_total", "Solicitudes HTTP atendidas"),
+        &["method", "route", "status"],
+    )
+    .unwrap();
+    let http_duracion = HistogramVec::new(
+        HistogramOpts::new("http_request_duration_seconds", "Duración de las solicitudes HTTP"),
+        &["method", "route", "status"],
+    )
+    .unwrap();
+    let http_en_curso = IntGauge::new("http_requests_in_flight", "Solicitudes HTTP en curso").unwrap();
+    let db_pool_conexiones = IntGaugeVec::new(
+        Opts::new("db_pool_connections", "Conexiones del pool de base de datos"),
+        &["pool", "state"],
+    )
+    .unwrap();
+    // bcrypt es lento a propósito: los buckets cubren de 1 ms a ~4 s
+    let hash_duracion = HistogramVec::new(
+        HistogramOpts::new("password_hash_duration_seconds", "Duración de hash y verificación de contraseñas")
+            .buckets(prometheus::exponential_buckets(0.001, 2.0, 13).unwrap()),
+        &["operation"],
+    )
+    .unwrap();
+    let logins = IntCounterVec::new(
+        Opts::new("auth_login_attempts_total", "Intentos de inicio de sesión"),
+        &["result"],
+    )
+    .unwrap();
+
+    registro.register(Box::new(http_solicitudes.clone())).unwrap();
+    registro.register(Box::new(http_duracion.clone())).unwrap();
+    registro.register(Box::new(http_en_curso.clone())).unwrap();
+    registro.register(Box::new(db_pool_conexiones.clone())).unwrap();
+    registro.register(Box::new(hash_duracion.clone())).unwrap();
+    registro.register(Box::new(logins.clone())).unwrap();
+
+    Metricas {
+        registro,
+        http_solicitudes,
+        http_duracion,
+        http_en_curso,
+        db_pool_conexiones,
+        hash_duracion,
+        logins,
+    }
+});
+
+/// Actualiza los gauges del pool con el estado actual de la conexión.
+pub fn registrar_pool(nombre: &str, db: &DatabaseConnection) {
+    if let Some(stats) = estadisticas_pool(db) {
+        let gauge = &METRICAS.db_pool_conexiones;
+        gauge.with_label_values(&[nombre, "size"]).set(stats.tamano.into());
+        gauge.with_label_values(&[nombre, "idle"]).set(stats.inactivas.into());
+        gauge.with_label_values(&[nombre, "in_use"]).set(stats.en_uso.into());
+        gauge.with_label_values(&[nombre, "max"]).set(stats.maximo.into());
+    }
+}
+
+/// Serializa todas las métricas en el formato de texto de Prometheus.
+pub fn exportar() -> Result<String, prometheus::Error> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&METRICAS.registro.gather(), &mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
@@ -1,2 +1,13 @@
+pub mod audit;
+pub mod email;
+pub mod etag;
 pub mod hash;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod lifecycle;
+pub mod metrics;
+pub mod password_policy;
+pub mod patch;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+#[cfg(feature = "tls")]
+pub mod tls;
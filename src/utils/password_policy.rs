@@ -0,0 +1,152 @@
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::config::env::{var_bool, var_o, var_opcional};
+use crate::errors::api_error::ErrorCampo;
+use crate::i18n::{Clave, Mensaje};
+
+/// Reglas que debe cumplir una contraseña nueva.
+///
+/// Se configura con variables de entorno:
+/// - `PASSWORD_MIN_LENGTH` (por defecto 8)
+/// - `PASSWORD_REQUIRE_UPPERCASE`, `PASSWORD_REQUIRE_LOWERCASE`,
+///   `PASSWORD_REQUIRE_DIGIT` (por defecto `true`) y `PASSWORD_REQUIRE_SYMBOL` (por defecto `false`)
+/// - `PASSWORD_FORBID_PERSONAL_DATA` (por defecto `true`)
+/// - `PASSWORD_BREACHED_DIR`: directorio con la lista local de contraseñas filtradas
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub longitud_minima: usize,
+    pub requiere_mayuscula: bool,
+    pub requiere_minuscula: bool,
+    pub requiere_digito: bool,
+    pub requiere_simbolo: bool,
+    pub prohibir_datos_personales: bool,
+    pub directorio_filtradas: Option<PathBuf>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            longitud_minima: 8,
+            requiere_mayuscula: true,
+            requiere_minuscula: true,
+            requiere_digito: true,
+            requiere_simbolo: false,
+            prohibir_datos_personales: true,
+            directorio_filtradas: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn from_env() -> Self {
+        let defecto = Self::default();
+
+        Self {
+            longitud_minima: var_o("PASSWORD_MIN_LENGTH", defecto.longitud_minima),
+            requiere_mayuscula: var_bool("PASSWORD_REQUIRE_UPPERCASE", defecto.requiere_mayuscula),
+            requiere_minuscula: var_bool("PASSWORD_REQUIRE_LOWERCASE", defecto.requiere_minuscula),
+            requiere_digito: var_bool("PASSWORD_REQUIRE_DIGIT", defecto.requiere_digito),
+            requiere_simbolo: var_bool("PASSWORD_REQUIRE_SYMBOL", defecto.requiere_simbolo),
+            prohibir_datos_personales: var_bool(
+                "PASSWORD_FORBID_PERSONAL_DATA",
+                defecto.prohibir_datos_personales,
+            ),
+            directorio_filtradas: var_opcional("PASSWORD_BREACHED_DIR").map(PathBuf::from),
+        }
+    }
+
+    /// Valida la contraseña y devuelve un error por cada regla incumplida,
+    /// asociado al campo `password`.
+    pub fn validar(&self, contraseña: &str, nombre: &str, email: &str) -> Vec<ErrorCampo> {
+        let mut errores = Vec::new();
+        let mut error = |mensaje: Mensaje| errores.push(ErrorCampo::new("password", mensaje));
+
+        if contraseña.chars().count() < self.longitud_minima {
+            error(Mensaje::new(Clave::ContraseñaCorta).con("minimo", self.longitud_minima));
+        }
+
+        if self.requiere_mayuscula && !contraseña.chars().any(char::is_uppercase) {
+            error(Clave::ContraseñaSinMayuscula.into());
+        }
+
+        if self.requiere_minuscula && !contraseña.chars().any(char::is_lowercase) {
+            error(Clave::ContraseñaSinMinuscula.into());
+        }
+
+        if self.requiere_digito && !contraseña.chars().any(|c| c.is_ascii_digit()) {
+            error(Clave::ContraseñaSinNumero.into());
+        }
+
+        if self.requiere_simbolo && contraseña.chars().all(char::is_alphanumeric) {
+            error(Clave::ContraseñaSinSimbolo.into());
+        }
+
+        if self.prohibir_datos_personales && contiene_datos_personales(contraseña, nombre, email) {
+            error(Clave::ContraseñaConDatosPersonales.into());
+        }
+
+        if let Some(directorio) = &self.directorio_filtradas {
+            match esta_filtrada(directorio, contraseña) {
+                Ok(true) => error(Clave::ContraseñaFiltrada.into()),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("No se pudo consultar la lista de contraseñas filtradas: {}", e),
+            }
+        }
+
+        errores
+    }
+}
+
+/// Partes del nombre y del email de 3 o más caracteres que no pueden
+/// aparecer dentro de la contraseña.
+fn contiene_datos_personales(contraseña: &str, nombre: &str, email: &str) -> bool {
+    let contraseña = contraseña.to_lowercase();
+    let local_email = email.split('@').next().unwrap_or_default();
+
+    nombre
+        .split_whitespace()
+        .chain(std::iter::once(local_email))
+        .map(str::to_lowercase)
+        .filter(|parte| parte.chars().count() >= 3)
+        .any(|parte| contraseña.contains(&parte))
+}
+
+/// Busca la contraseña en una lista local con el formato de rangos
+/// k-anonymity de Have I Been Pwned: un archivo por prefijo de 5 caracteres
+/// del SHA-1 (`21BD1` o `21BD1.txt`, también en minúsculas) con líneas
+/// `SUFIJO:CONTEO`. Las líneas con conteo 0 son relleno y no cuentan.
+fn esta_filtrada(directorio: &Path, contraseña: &str) -> std::io::Result<bool> {
+    let hash: String = Sha1::digest(contraseña.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect();
+    let (prefijo, sufijo) = hash.split_at(5);
+
+    let minusculas = prefijo.to_lowercase();
+    let nombres = [prefijo.to_string(), format!("{}.txt", prefijo), minusculas.clone(), format!("{}.txt", minusculas)];
+
+    for nombre_archivo in nombres {
+        let contenido = match fs::read_to_string(directorio.join(nombre_archivo)) {
+            Ok(contenido) => contenido,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        return Ok(contenido.lines().any(|linea| {
+            let mut partes = linea.trim().split(':');
+            let coincide = partes
+                .next()
+                .is_some_and(|s| s.eq_ignore_ascii_case(sufijo));
+            let conteo = partes
+                .next()
+                .and_then(|c| c.trim().parse::<u64>().ok())
+                .unwrap_or(1);
+            coincide && conteo > 0
+        }));
+    }
+
+    Ok(false)
+}
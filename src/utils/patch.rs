@@ -0,0 +1,57 @@
+use actix_web::{HttpMessage, HttpRequest};
+use json_patch::{Patch, PatchErrorKind};
+use serde_json::Value;
+
+use crate::errors::api_error::ApiError;
+use crate::i18n::{Clave, Mensaje};
+
+pub const MERGE_PATCH: &str = "application/merge-patch+json";
+pub const JSON_PATCH: &str = "application/json-patch+json";
+
+/// Aplica sobre `documento` el cuerpo de una solicitud `PATCH`, según su
+/// `Content-Type`: JSON Merge Patch (RFC 7396) o JSON Patch (RFC 6902).
+pub fn aplicar(req: &HttpRequest, documento: &mut Value, cuerpo: &[u8]) -> Result<(), ApiError> {
+    match req.content_type() {
+        MERGE_PATCH => {
+            let parche: Value = serde_json::from_slice(cuerpo)
+                .map_err(|e| ApiError::bad_request(Mensaje::new(Clave::MergePatchInvalido).con("detalle", e)))?;
+
+            json_patch::merge(documento, &parche);
+            Ok(())
+        }
+        JSON_PATCH => {
+            let parche: Patch = serde_json::from_slice(cuerpo)
+                .map_err(|e| ApiError::bad_request(Mensaje::new(Clave::JsonPatchInvalido).con("detalle", e)))?;
+
+            // `patch` es atómico: si una operación falla, el documento no cambia
+            json_patch::patch(documento, &parche).map_err(|e| match e.kind {
+                PatchErrorKind::TestFailed => ApiError::conflict(
+                    Mensaje::new(Clave::PruebaPatchFallida)
+                        .con("operacion", e.operation)
+                        .con("ruta", &e.path),
+                ),
+                _ => ApiError::unprocessable_entity(Mensaje::new(Clave::PatchNoAplicable).con("detalle", e)),
+            })
+        }
+        _ => Err(ApiError::unsupported_media_type(
+            Mensaje::new(Clave::TipoPatchNoSoportado).con("tipos", format!("{} o {}", MERGE_PATCH, JSON_PATCH)),
+        )),
+    }
+}
+
+/// Campos de primer nivel cuyo valor difiere entre ambos documentos.
+pub fn campos_modificados(antes: &Value, despues: &Value) -> Vec<String> {
+    let vacio = serde_json::Map::new();
+    let antes = antes.as_object().unwrap_or(&vacio);
+    let despues = despues.as_object().unwrap_or(&vacio);
+
+    let mut campos: Vec<String> = antes
+        .keys()
+        .chain(despues.keys())
+        .filter(|campo| antes.get(*campo) != despues.get(*campo))
+        .cloned()
+        .collect();
+    campos.sort();
+    campos.dedup();
+    campos
+}
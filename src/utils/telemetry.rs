@@ -0,0 +1,150 @@
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::{Span as _, SpanKind, Status, Tracer as _, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::env::{var_bool, var_opcional};
+
+const NOMBRE_SERVICIO: &str = "rust-api";
+
+static PROVEEDOR: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Configura el exportador OTLP y el propagador W3C `traceparent`, y devuelve
+/// la capa de `tracing` que exporta los spans.
+///
+/// El exportador usa las variables estándar de OpenTelemetry
+/// (`OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_EXPORTER_OTLP_TIMEOUT`, ...);
+/// `OTEL_SERVICE_NAME` cambia el nombre del servicio y `OTEL_SDK_DISABLED=true`
+/// desactiva la exportación sin recompilar.
+pub fn inicializar<S>() -> Option<OpenTelemetryLayer<S, SdkTracer>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    if var_bool("OTEL_SDK_DISABLED", false) {
+        return None;
+    }
+
+    // Se llama antes de configurar tracing: los errores van a stderr
+    let exportador = match SpanExporter::builder().with_tonic().build() {
+        Ok(exportador) => exportador,
+        Err(e) => {
+            eprintln!("No se pudo crear el exportador OTLP: {}", e);
+            return None;
+        }
+    };
+
+    let nombre = var_opcional("OTEL_SERVICE_NAME").unwrap_or_else(|| NOMBRE_SERVICIO.to_string());
+    let proveedor = SdkTracerProvider::builder()
+        .with_batch_exporter(exportador)
+        .with_resource(Resource::builder().with_service_name(nombre).build())
+        .build();
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(proveedor.clone());
+    let tracer = proveedor.tracer(NOMBRE_SERVICIO);
+    PROVEEDOR.set(proveedor).ok();
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Envía los spans pendientes antes de terminar el proceso.
+pub fn finalizar() {
+    if let Some(proveedor) = PROVEEDOR.get() {
+        if let Err(e) = proveedor.shutdown() {
+            tracing::warn!("Error al cerrar el exportador OTLP: {}", e);
+        }
+    }
+}
+
+/// Usa el `traceparent` recibido como padre del span de la solicitud.
+pub fn extraer_contexto(span: &tracing::Span, cabeceras: &HeaderMap) {
+    let padre = global::get_text_map_propagator(|propagador| {
+        propagador.extract(&ExtractorCabeceras(cabeceras))
+    });
+    span.set_parent(padre);
+}
+
+/// Escribe el `traceparent` del span en las cabeceras de la respuesta.
+pub fn inyectar_contexto(span: &tracing::Span, cabeceras: &mut HeaderMap) {
+    let contexto = span.context();
+    global::get_text_map_propagator(|propagador| {
+        propagador.inject_context(&contexto, &mut InyectorCabeceras(cabeceras))
+    });
+}
+
+/// Registra un span hijo por cada consulta SeaORM ejecutada en `db`.
+pub fn instrumentar_consultas(db: &mut DatabaseConnection) {
+    let sistema = match db.get_database_backend() {
+        DbBackend::Postgres => "postgresql",
+        DbBackend::MySql => "mysql",
+        DbBackend::Sqlite => "sqlite",
+    };
+
+    db.set_metric_callback(move |info| {
+        // SeaORM avisa al terminar la consulta: el span se crea con su inicio real
+        let fin = SystemTime::now();
+        let inicio = fin.checked_sub(info.elapsed).unwrap_or(fin);
+        let padre = tracing::Span::current().context();
+        let tracer = global::tracer(NOMBRE_SERVICIO);
+
+        let mut span = tracer
+            .span_builder(resumen_consulta(&info.statement.sql))
+            .with_kind(SpanKind::Client)
+            .with_start_time(inicio)
+            .with_attributes([
+                KeyValue::new("db.system", sistema),
+                KeyValue::new("db.statement", info.statement.sql.clone()),
+            ])
+            .start_with_context(&tracer, &padre);
+
+        if info.failed {
+            span.set_status(Status::error("La consulta falló"));
+        }
+        span.end_with_timestamp(fin);
+    });
+}
+
+// Nombre corto del span según la operación SQL: `db.select`, `db.insert`, ...
+fn resumen_consulta(sql: &str) -> String {
+    let operacion = sql.split_whitespace().next().unwrap_or("sql");
+    format!("db.{}", operacion.to_lowercase())
+}
+
+struct ExtractorCabeceras<'a>(&'a HeaderMap);
+
+impl Extractor for ExtractorCabeceras<'_> {
+    fn get(&self, clave: &str) -> Option<&str> {
+        self.0.get(clave).and_then(|valor| valor.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct InyectorCabeceras<'a>(&'a mut HeaderMap);
+
+impl Injector for InyectorCabeceras<'_> {
+    fn set(&mut self, clave: &str, valor: String) {
+        if valor.is_empty() {
+            return;
+        }
+        if let (Ok(nombre), Ok(valor)) = (
+            HeaderName::from_bytes(clave.as_bytes()),
+            HeaderValue::from_str(&valor),
+        ) {
+            self.0.insert(nombre, valor);
+        }
+    }
+}
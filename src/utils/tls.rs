@@ -0,0 +1,198 @@
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::dev::Extensions;
+use actix_web::rt::net::TcpStream;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use rustls::crypto::CryptoProvider;
+use rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use rustls::sign::CertifiedKey;
+use rustls::{RootCertStore, ServerConfig};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::tls::TlsSettings;
+
+/// Identidad del cliente autenticado con certificado (mTLS). Se guarda en las
+/// extensiones de la solicitud.
+#[derive(Debug, Clone)]
+pub struct ClientPrincipal {
+    /// Sujeto completo del certificado, p. ej. `CN=servicio-a, O=Ejemplo`.
+    pub sujeto: String,
+    pub nombre_comun: Option<String>,
+}
+
+/// Certificado del servidor que se puede reemplazar sin reiniciar.
+#[derive(Debug)]
+pub struct CertificadoRecargable {
+    actual: RwLock<Arc<CertifiedKey>>,
+    proveedor: Arc<CryptoProvider>,
+}
+
+impl CertificadoRecargable {
+    fn cargar(proveedor: Arc<CryptoProvider>, ajustes: &TlsSettings) -> io::Result<Self> {
+        let certificado = leer_certificado(&proveedor, ajustes)?;
+        Ok(Self { actual: RwLock::new(Arc::new(certificado)), proveedor })
+    }
+
+    fn recargar(&self, ajustes: &TlsSettings) -> io::Result<()> {
+        let certificado = leer_certificado(&self.proveedor, ajustes)?;
+        *self.actual.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(certificado);
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for CertificadoRecargable {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.actual.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+}
+
+/// Configuración rustls del servidor. ALPN (`h2` y `http/1.1`) lo añade actix-web.
+pub fn configurar(ajustes: &TlsSettings) -> io::Result<(ServerConfig, Arc<CertificadoRecargable>)> {
+    let proveedor = Arc::new(rustls::crypto::ring::default_provider());
+    let certificado = Arc::new(CertificadoRecargable::cargar(proveedor.clone(), ajustes)?);
+
+    let builder = ServerConfig::builder_with_provider(proveedor.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?;
+
+    let builder = match &ajustes.ca_clientes {
+        Some(ruta) => {
+            let mut raices = RootCertStore::empty();
+            for ca in CertificateDer::pem_file_iter(ruta).map_err(error_pem(ruta))? {
+                raices.add(ca.map_err(error_pem(ruta))?).map_err(io::Error::other)?;
+            }
+
+            let verificador = WebPkiClientVerifier::builder_with_provider(Arc::new(raices), proveedor);
+            let verificador = if ajustes.cliente_obligatorio {
+                verificador
+            } else {
+                verificador.allow_unauthenticated()
+            };
+            builder.with_client_cert_verifier(verificador.build().map_err(io::Error::other)?)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    Ok((builder.with_cert_resolver(certificado.clone()), certificado))
+}
+
+/// Vigila el certificado y la clave y los recarga cuando cambian. Si el
+/// archivo nuevo es inválido se mantiene el certificado anterior.
+pub fn vigilar_certificado(
+    certificado: Arc<CertificadoRecargable>,
+    ajustes: TlsSettings,
+    cancelacion: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ultima_modificacion = modificacion(&ajustes);
+        let mut intervalo = tokio::time::interval(ajustes.intervalo_recarga);
+
+        loop {
+            tokio::select! {
+                _ = cancelacion.cancelled() => break,
+                _ = intervalo.tick() => {}
+            }
+
+            let modificado = modificacion(&ajustes);
+            if modificado == ultima_modificacion {
+                continue;
+            }
+            ultima_modificacion = modificado;
+
+            match certificado.recargar(&ajustes) {
+                Ok(()) => tracing::info!("Certificado TLS recargado desde {}", ajustes.certificado.display()),
+                Err(e) => tracing::error!("No se pudo recargar el certificado TLS: {}", e),
+            }
+        }
+    })
+}
+
+/// Guarda el certificado de cliente de la conexión (si lo hay) en los datos
+/// de conexión; el middleware de autenticación lo pasa a la solicitud.
+pub fn registrar_certificado_cliente(conexion: &dyn Any, datos: &mut Extensions) {
+    let Some(tls) = conexion.downcast_ref::<TlsStream<TcpStream>>() else {
+        return;
+    };
+
+    let principal = tls
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|cadena| cadena.first())
+        .and_then(|certificado| x509_parser::parse_x509_certificate(certificado).ok())
+        .map(|(_, certificado)| ClientPrincipal {
+            sujeto: certificado.subject().to_string(),
+            nombre_comun: certificado
+                .subject()
+                .iter_common_name()
+                .next()
+                .and_then(|cn| cn.as_str().ok())
+                .map(str::to_string),
+        });
+
+    if let Some(principal) = principal {
+        datos.insert(principal);
+    }
+}
+
+/// Puerto HTTPS al que redirige el servidor HTTP.
+#[derive(Debug, Clone, Copy)]
+pub struct PuertoHttps(pub u16);
+
+/// Responde `308` con la misma URL en HTTPS.
+pub async fn redirigir_a_https(req: HttpRequest, puerto: web::Data<PuertoHttps>) -> HttpResponse {
+    let info = req.connection_info();
+    let host = info.host();
+    // Quitar el puerto HTTP del host (sin romper IPv6 como `[::1]:80`)
+    let host = match host.rsplit_once(':') {
+        Some((nombre, puerto)) if puerto.chars().all(|c| c.is_ascii_digit()) => nombre,
+        _ => host,
+    };
+    let puerto = match puerto.0 {
+        443 => String::new(),
+        otro => format!(":{}", otro),
+    };
+    let destino = format!("https://{}{}{}", host, puerto, req.uri().path_and_query().map_or("/", |pq| pq.as_str()));
+
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, destino))
+        .finish()
+}
+
+fn leer_certificado(proveedor: &CryptoProvider, ajustes: &TlsSettings) -> io::Result<CertifiedKey> {
+    let cadena = CertificateDer::pem_file_iter(&ajustes.certificado)
+        .map_err(error_pem(&ajustes.certificado))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error_pem(&ajustes.certificado))?;
+    if cadena.is_empty() {
+        return Err(io::Error::other(format!(
+            "{} no contiene ningún certificado",
+            ajustes.certificado.display()
+        )));
+    }
+
+    let clave = PrivateKeyDer::from_pem_file(&ajustes.clave).map_err(error_pem(&ajustes.clave))?;
+
+    CertifiedKey::from_der(cadena, clave, proveedor).map_err(io::Error::other)
+}
+
+// Fecha de modificación más reciente entre el certificado y la clave
+fn modificacion(ajustes: &TlsSettings) -> Option<SystemTime> {
+    [&ajustes.certificado, &ajustes.clave]
+        .into_iter()
+        .filter_map(|ruta| fs::metadata(ruta).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+fn error_pem(ruta: &Path) -> impl Fn(rustls_pki_types::pem::Error) -> io::Error + '_ {
+    move |e| io::Error::other(format!("{}: {}", ruta.display(), e))
+}
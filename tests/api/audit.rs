@@ -0,0 +1,110 @@
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::test::{self, TestRequest};
+use serde_json::{json, Value};
+
+use super::support::{datos_registro, llamar_autenticada, registrar, usuario_con_token, Contexto, UsuarioFactory, CONTRASEÑA};
+use rust_api::config::proxies::TrustedProxies;
+use rust_api::models::audit_log;
+
+fn ip(valor: &str) -> IpAddr {
+    valor.parse().unwrap()
+}
+
+#[actix_web::test]
+async fn auditoria_requiere_administrador() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/auditoria"), &token).await;
+    assert_eq!(res.status(), 403);
+}
+
+#[actix_web::test]
+async fn auditoria_registra_y_filtra_eventos() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let registrado = registrar(&app, &datos_registro()).await;
+    let (admin, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/auditoria"), &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["paginacion"]["total"], 2);
+
+    let uri = format!("/api/auditoria?accion={}", audit_log::USER_REGISTERED);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    let registros = cuerpo["datos"].as_array().unwrap();
+    assert_eq!(registros.len(), 1);
+    assert_eq!(registros[0]["target_id"], registrado["id"]);
+
+    let uri = format!("/api/auditoria?actor_id={}&por_pagina=1", admin.id);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["datos"][0]["action"], audit_log::LOGIN_SUCCESS);
+    assert_eq!(cuerpo["paginacion"]["por_pagina"], 1);
+}
+
+#[actix_web::test]
+async fn la_ip_auditada_no_se_toma_de_cabeceras_del_cliente() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (admin, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .peer_addr("203.0.113.7:50000".parse::<SocketAddr>().unwrap())
+        .insert_header(("X-Forwarded-For", "10.9.9.9"))
+        .insert_header(("Forwarded", "for=10.9.9.9"))
+        .set_json(json!({ "email": admin.email, "password": CONTRASEÑA }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let uri = format!("/api/auditoria?actor_id={}&accion={}", admin.id, audit_log::LOGIN_SUCCESS);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    let ips: Vec<&Value> = cuerpo["datos"].as_array().unwrap().iter().map(|r| &r["ip"]).collect();
+    assert!(ips.contains(&&json!("203.0.113.7")));
+    assert!(!ips.contains(&&json!("10.9.9.9")));
+}
+
+#[test]
+fn x_forwarded_for_solo_desde_proxies_de_confianza() {
+    let proxies = TrustedProxies::new(vec![ip("10.0.0.1"), ip("10.0.0.2")]);
+
+    // Sin proxy de confianza se ignora la cabecera
+    assert_eq!(proxies.ip_cliente(ip("198.51.100.4"), Some("1.2.3.4")), ip("198.51.100.4"));
+
+    // Se salta la cadena de proxies de confianza desde la derecha
+    let cadena = Some("1.2.3.4, 198.51.100.4, 10.0.0.2");
+    assert_eq!(proxies.ip_cliente(ip("10.0.0.1"), cadena), ip("198.51.100.4"));
+
+    // Sin cabecera, la del proxy
+    assert_eq!(proxies.ip_cliente(ip("10.0.0.1"), None), ip("10.0.0.1"));
+}
+
+#[actix_web::test]
+async fn una_actualizacion_sin_cambios_no_se_audita() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (admin, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let uri = format!("/api/usuarios/{}", admin.id);
+    let auditados = format!("/api/auditoria?objetivo_id={}&accion={}", admin.id, audit_log::USER_UPDATED);
+
+    // Mismo nombre: solo cambiarían `version` y `updated_at`
+    let req = TestRequest::put().uri(&uri).set_json(json!({ "name": admin.name }));
+    assert_eq!(llamar_autenticada(&app, req, &token).await.status(), 200);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&auditados), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["paginacion"]["total"], 0);
+
+    let req = TestRequest::put().uri(&uri).set_json(json!({ "name": "Otro Nombre" }));
+    assert_eq!(llamar_autenticada(&app, req, &token).await.status(), 200);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&auditados), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["paginacion"]["total"], 1);
+    assert_eq!(cuerpo["datos"][0]["changes"], json!({ "name": { "antes": admin.name, "despues": "Otro Nombre" } }));
+}
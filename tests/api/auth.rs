@@ -0,0 +1,146 @@
+use actix_web::test::{self, TestRequest};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, IntoActiveModel, Set};
+use serde_json::{json, Value};
+
+use super::support::{
+    datos_registro, llamar_autenticada, login, registrar, usuario_con_token, Contexto, UsuarioFactory, CONTRASEÑA,
+};
+
+#[actix_web::test]
+async fn registro_crea_el_usuario() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let datos = datos_registro();
+
+    let usuario = registrar(&app, &datos).await;
+    assert_eq!(usuario["email"], datos["email"]);
+    assert_eq!(usuario["role"], "user");
+    assert_eq!(usuario["version"], 1);
+}
+
+#[actix_web::test]
+async fn registro_aplica_la_politica_de_contraseñas() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let mut datos = datos_registro();
+    datos["password"] = json!("corta");
+
+    let req = TestRequest::post().uri("/api/auth/registro").set_json(&datos).to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "password");
+}
+
+#[actix_web::test]
+async fn registro_con_email_en_uso() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let datos = datos_registro();
+    registrar(&app, &datos).await;
+
+    let req = TestRequest::post().uri("/api/auth/registro").set_json(&datos).to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 409);
+}
+
+#[actix_web::test]
+async fn login_con_usuario_registrado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let datos = datos_registro();
+    registrar(&app, &datos).await;
+
+    let token = login(&app, datos["email"].as_str().unwrap(), CONTRASEÑA).await;
+    assert!(!token.is_empty());
+}
+
+#[actix_web::test]
+async fn login_con_contraseña_incorrecta() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let usuario = UsuarioFactory::new().crear(&ctx.db).await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": usuario.email, "password": "Incorrecta123" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 401);
+}
+
+#[actix_web::test]
+async fn login_con_email_desconocido() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "nadie@ejemplo.com", "password": CONTRASEÑA }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 401);
+}
+
+#[actix_web::test]
+async fn login_sin_credenciales() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": "", "password": "" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+async fn login_de_usuario_deshabilitado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let usuario = UsuarioFactory::new().crear(&ctx.db).await;
+    let email = usuario.email.clone();
+
+    let mut activo = usuario.into_active_model();
+    activo.disabled_at = Set(Some(Utc::now()));
+    activo.update(&ctx.db).await.unwrap();
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": email, "password": CONTRASEÑA }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 403);
+}
+
+#[actix_web::test]
+async fn deshabilitar_invalida_los_tokens_emitidos() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (admin, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    let mut activo = admin.into_active_model();
+    activo.disabled_at = Set(Some(Utc::now()));
+    activo.update(&ctx.db).await.unwrap();
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios"), &token).await;
+    assert_eq!(res.status(), 403);
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/auditoria"), &token).await;
+    assert_eq!(res.status(), 403);
+}
+
+#[actix_web::test]
+async fn token_invalido_es_rechazado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let req = TestRequest::get()
+        .uri("/api/usuarios")
+        .insert_header(("Authorization", "Bearer no-es-un-token"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 401);
+}
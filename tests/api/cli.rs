@@ -0,0 +1,65 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use serde_json::Value;
+
+use super::support::{preparar_entorno, CONTRASEÑA};
+
+// Ejecuta el binario sobre `base`, desde un directorio sin `.env`
+fn rust_api(base: &str, argumentos: &[&str], contraseña: Option<&str>, entrada: &str) -> Output {
+    preparar_entorno();
+    let mut comando = Command::new(env!("CARGO_BIN_EXE_rust-api"));
+    comando
+        .args(argumentos)
+        .current_dir(std::env::temp_dir())
+        .env("DATABASE_URL", base)
+        .env_remove("USER_PASSWORD")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(contraseña) = contraseña {
+        comando.env("USER_PASSWORD", contraseña);
+    }
+
+    let mut proceso = comando.spawn().unwrap();
+    proceso.stdin.take().unwrap().write_all(entrada.as_bytes()).unwrap();
+    proceso.wait_with_output().unwrap()
+}
+
+fn error(salida: &Output) -> String {
+    String::from_utf8_lossy(&salida.stderr).into_owned()
+}
+
+#[test]
+fn los_comandos_de_usuarios_leen_la_contraseña_fuera_de_los_argumentos() {
+    let archivo = std::env::temp_dir().join(format!("rust-api-cli-{}.db", uuid::Uuid::new_v4()));
+    let base = format!("sqlite://{}?mode=rwc", archivo.display());
+    let crear = ["users", "create", "--name", "Ana Admin", "--email", "ana@ejemplo.com", "--format", "json"];
+
+    // No aplica migraciones por su cuenta
+    let salida = rust_api(&base, &crear, Some(CONTRASEÑA), "");
+    assert!(!salida.status.success());
+    assert!(error(&salida).contains("migrate up"), "{}", error(&salida));
+
+    assert!(rust_api(&base, &["migrate", "up"], None, "").status.success());
+
+    let salida = rust_api(&base, &crear, Some(CONTRASEÑA), "");
+    assert!(salida.status.success(), "{}", error(&salida));
+    let usuario: Value = serde_json::from_slice(&salida.stdout).unwrap();
+    assert_eq!(usuario["email"], "ana@ejemplo.com");
+
+    // Sin USER_PASSWORD se lee de la entrada estándar y se aplica la política
+    let reiniciar = ["users", "reset-password", "ana@ejemplo.com"];
+    let salida = rust_api(&base, &reiniciar, None, "corta\n");
+    assert!(!salida.status.success());
+    let salida = rust_api(&base, &reiniciar, None, "Otra-Clave-2\n");
+    assert!(salida.status.success(), "{}", error(&salida));
+    let salida = rust_api(&base, &reiniciar, None, "");
+    assert!(error(&salida).contains("USER_PASSWORD"), "{}", error(&salida));
+
+    // La contraseña ya no se acepta como argumento
+    let salida = rust_api(&base, &["users", "reset-password", "ana@ejemplo.com", "--password", "x"], None, "");
+    assert!(!salida.status.success());
+
+    std::fs::remove_file(archivo).ok();
+}
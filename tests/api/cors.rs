@@ -0,0 +1,46 @@
+use rust_api::config::cors::OriginPattern;
+
+#[test]
+fn los_origenes_permitidos_coinciden_solo_con_su_patron() {
+    let casos = [
+        // (patrón, origen, coincide)
+        ("https://app.ejemplo.com", "https://app.ejemplo.com", true),
+        ("https://app.ejemplo.com/", "https://APP.ejemplo.com", true),
+        ("https://app.ejemplo.com", "https://app.ejemplo.com:8443", false),
+        ("https://app.ejemplo.com", "http://app.ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://app.ejemplo.com", true),
+        ("https://*.ejemplo.com", "https://a.b.ejemplo.com", true),
+        ("https://*.ejemplo.com", "https://mi-app.ejemplo.com", true),
+        ("https://*.ejemplo.com", "https://ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://.ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://a..ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://-app.ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://evil-ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://a.ejemplo.com.evil.io", false),
+        ("https://*.ejemplo.com", "https://evil.io/a.ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://evil.io@a.ejemplo.com", false),
+        ("https://*.ejemplo.com", "http://app.ejemplo.com", false),
+        ("https://*.ejemplo.com", "wss://app.ejemplo.com", false),
+        ("https://*.ejemplo.com", "https://app.ejemplo.com:8443", false),
+        ("https://*.ejemplo.com:8443", "https://app.ejemplo.com:8443", true),
+        ("https://*.ejemplo.com:8443", "https://app.ejemplo.com", false),
+        ("https://*.ejemplo.com:8443", "https://app.ejemplo.com:9443", false),
+        ("http://*.localhost", "http://app.localhost", true),
+    ];
+
+    for (patron, origen, coincide) in casos {
+        assert_eq!(OriginPattern::parse(patron).coincide(origen), coincide, "{} con {}", patron, origen);
+    }
+}
+
+#[test]
+fn los_patrones_se_interpretan_como_exactos_o_de_subdominio() {
+    assert_eq!(
+        OriginPattern::parse("https://*.Ejemplo.com/"),
+        OriginPattern::Subdominio { prefijo: "https://".to_string(), sufijo: ".ejemplo.com".to_string() }
+    );
+    assert_eq!(
+        OriginPattern::parse("https://App.ejemplo.com/"),
+        OriginPattern::Exacto("https://app.ejemplo.com".to_string())
+    );
+}
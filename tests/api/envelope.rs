@@ -0,0 +1,112 @@
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use serde_json::{json, Value};
+
+use super::support::{
+    datos_registro, llamar_autenticada, registrar, usuario_con_token, Contexto, UsuarioFactory, CONTRASEÑA,
+};
+
+#[actix_web::test]
+async fn la_v2_envuelve_las_respuestas() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::get().uri("/api/v2/usuarios").insert_header(("x-request-id", "sobre-1"));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["data"][0]["id"], usuario.id);
+    assert_eq!(cuerpo["meta"]["request_id"], "sobre-1");
+    assert_eq!(cuerpo["meta"]["api_version"], 2);
+    assert_eq!(cuerpo["links"]["self"], "/api/v2/usuarios");
+
+    // Las cabeceras del handler se conservan
+    let uri = format!("/api/v2/usuarios/{}", usuario.id);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    assert!(res.headers().contains_key(header::ETAG));
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["data"]["email"], usuario.email.as_str());
+
+    let res = llamar_autenticada(&app, TestRequest::delete().uri(&uri), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["data"].is_null());
+    assert_eq!(cuerpo["meta"]["message"], "Usuario eliminado exitosamente");
+}
+
+#[actix_web::test]
+async fn la_v1_mantiene_el_formato_anterior() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios"), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo[0]["id"], usuario.id);
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/v1/perfil"), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["exito"], true);
+    assert_eq!(cuerpo["datos"]["nombre"], usuario.name.as_str());
+}
+
+#[actix_web::test]
+async fn login_en_la_v2() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let datos = datos_registro();
+    registrar(&app, &datos).await;
+
+    let req = TestRequest::post()
+        .uri("/api/v2/auth/login")
+        .set_json(json!({ "email": datos["email"], "password": CONTRASEÑA }));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["data"]["token"].is_string());
+    assert_eq!(cuerpo["data"]["user"]["email"], datos["email"]);
+    assert!(cuerpo["data"]["user"]["name"].is_string());
+    assert_eq!(cuerpo["meta"]["message"], "Inicio de sesión exitoso");
+    assert!(cuerpo.get("success").is_none());
+}
+
+#[actix_web::test]
+async fn la_paginacion_va_en_meta_y_links() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    for _ in 0..2 {
+        registrar(&app, &datos_registro()).await;
+    }
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    // 2 registros y 1 login: 3 páginas de 1
+    let req = TestRequest::get().uri("/api/v2/auditoria?por_pagina=1&pagina=2");
+    let res = llamar_autenticada(&app, req, &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+
+    assert_eq!(cuerpo["data"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        cuerpo["meta"]["pagination"],
+        json!({ "page": 2, "per_page": 1, "total": 3, "total_pages": 3 })
+    );
+    assert_eq!(cuerpo["links"]["self"], "/api/v2/auditoria?por_pagina=1&pagina=2");
+    assert_eq!(cuerpo["links"]["first"], "/api/v2/auditoria?por_pagina=1&pagina=1");
+    assert_eq!(cuerpo["links"]["prev"], "/api/v2/auditoria?por_pagina=1&pagina=1");
+    assert_eq!(cuerpo["links"]["next"], "/api/v2/auditoria?por_pagina=1&pagina=3");
+    assert_eq!(cuerpo["links"]["last"], "/api/v2/auditoria?por_pagina=1&pagina=3");
+}
+
+#[actix_web::test]
+async fn las_sondas_se_envuelven_si_se_pide_la_v2() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let req = TestRequest::get()
+        .uri("/api/salud")
+        .insert_header((header::ACCEPT, "application/vnd.api+json;version=2"));
+    let res = test::call_service(&app, req.to_request()).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["data"]["estado"], "OK");
+    assert_eq!(cuerpo["meta"]["message"], "Servidor funcionando correctamente");
+}
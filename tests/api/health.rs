@@ -0,0 +1,153 @@
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::header::HeaderValue;
+use actix_web::test::{self, TestRequest};
+use actix_web::{web, App, HttpResponse};
+use rust_api::middleware::request_id::{request_id_actual, RequestIdentifier};
+use sea_orm::ConnectionTrait;
+use serde_json::Value;
+
+use super::support::{llamar_autenticada, usuario_con_token, Contexto, UsuarioFactory};
+
+#[actix_web::test]
+async fn salud_responde_ok() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["estado"], "OK");
+}
+
+#[actix_web::test]
+async fn liveness_responde_vivo() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud/vivo").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["estado"], "vivo");
+}
+
+#[actix_web::test]
+async fn readiness_comprueba_dependencias() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud/listo").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["estado"], "listo");
+    assert_eq!(cuerpo["componentes"]["base_de_datos"]["estado"], "ok");
+    assert_eq!(cuerpo["componentes"]["migraciones"]["estado"], "ok");
+
+    // Cada componente expone solo su estado y su latencia
+    for (nombre, componente) in cuerpo["componentes"].as_object().unwrap() {
+        let campos: Vec<_> = componente.as_object().unwrap().keys().map(String::as_str).collect();
+        assert!(campos.iter().all(|c| ["estado", "latencia_ms"].contains(c)), "{}: {:?}", nombre, campos);
+    }
+}
+
+#[actix_web::test]
+async fn readiness_no_expone_el_detalle_de_los_fallos() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    ctx.db.execute_unprepared("DELETE FROM seaql_migrations").await.unwrap();
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud/listo").to_request()).await;
+    assert_eq!(res.status(), 503);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["componentes"]["migraciones"]["estado"], "pendientes");
+    assert!(!cuerpo.to_string().contains("m2026"), "{}", cuerpo);
+}
+
+#[actix_web::test]
+async fn readiness_falla_durante_el_apagado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    ctx.estado.lifecycle.iniciar_apagado();
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud/listo").to_request()).await;
+    assert_eq!(res.status(), 503);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["apagando"], true);
+}
+
+#[actix_web::test]
+async fn info_lista_los_endpoints() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/info").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["endpoints"]["POST /api/auth/login"].is_string());
+}
+
+#[actix_web::test]
+async fn perfil_requiere_token() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/perfil").to_request()).await;
+    assert_eq!(res.status(), 401);
+}
+
+#[actix_web::test]
+async fn perfil_devuelve_el_usuario_autenticado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let fabrica = UsuarioFactory::new().nombre("Ana Pérez").email("ana@ejemplo.com");
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, fabrica).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/perfil"), &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["datos"]["id"], usuario.id);
+    assert_eq!(cuerpo["datos"]["nombre"], "Ana Pérez");
+    assert_eq!(cuerpo["datos"]["email"], "ana@ejemplo.com");
+}
+
+#[actix_web::test]
+async fn las_respuestas_llevan_request_id_y_cabeceras_de_seguridad() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud").to_request()).await;
+    assert!(res.headers().contains_key("x-request-id"));
+    assert_eq!(res.headers().get("x-content-type-options").unwrap(), "nosniff");
+}
+
+#[actix_web::test]
+async fn el_request_id_cubre_los_middlewares_y_los_errores() {
+    let app = test::init_service(
+        App::new()
+            // Anota el request id visto en la parte síncrona de `call` y falla
+            .wrap_fn(|req, srv| {
+                let visto = request_id_actual().unwrap_or_default();
+                let fut = srv.call(req);
+                async move {
+                    fut.await?;
+                    Err::<ServiceResponse, _>(actix_web::error::ErrorForbidden(visto))
+                }
+            })
+            .wrap(RequestIdentifier)
+            .route("/", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+
+    // El servidor responde el error con `error_response`, como aquí
+    let req = TestRequest::get().uri("/").insert_header(("x-request-id", "id-de-prueba")).to_request();
+    let res = app.call(req).await.expect_err("el middleware debería fallar").error_response();
+    assert_eq!(res.status(), 403);
+    assert_eq!(res.headers().get("x-request-id"), Some(&HeaderValue::from_static("id-de-prueba")));
+    assert_eq!(actix_web::body::to_bytes(res.into_body()).await.unwrap(), "id-de-prueba");
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use rust_api::i18n::{Clave, Idioma, Mensaje};
+use serde_json::{json, Value};
+
+use super::support::{
+    autenticada, datos_registro, llamar_autenticada, login, registrar, usuario_con_token, Contexto,
+    UsuarioFactory, CONTRASEÑA,
+};
+
+#[test]
+fn el_catalogo_tiene_codigos_unicos_y_los_mismos_parametros() {
+    let mut codigos = HashSet::new();
+
+    for clave in Clave::TODAS {
+        assert!(codigos.insert(clave.codigo()), "código repetido: {}", clave.codigo());
+
+        let marcadores = |texto: &str| -> Vec<String> {
+            let mut marcadores: Vec<String> = texto
+                .split('{')
+                .skip(1)
+                .filter_map(|resto| resto.split_once('}').map(|(nombre, _)| nombre.to_string()))
+                .collect();
+            marcadores.sort();
+            marcadores
+        };
+        assert_eq!(
+            marcadores(clave.texto(Idioma::Es)),
+            marcadores(clave.texto(Idioma::En)),
+            "{}: los idiomas usan parámetros distintos",
+            clave.codigo()
+        );
+    }
+
+    let mensaje = Mensaje::new(Clave::ContraseñaCorta).con("minimo", 12);
+    assert_eq!(mensaje.traducir(Idioma::En), "The password must be at least 12 characters long");
+}
+
+#[test]
+fn accept_language_respeta_la_calidad() {
+    assert_eq!(Idioma::desde_accept_language("en-US,en;q=0.9"), Some(Idioma::En));
+    assert_eq!(Idioma::desde_accept_language("fr, en;q=0.5, es;q=0.8"), Some(Idioma::Es));
+    assert_eq!(Idioma::desde_accept_language("es;q=0, en;q=0.1"), Some(Idioma::En));
+    assert_eq!(Idioma::desde_accept_language("fr, de"), None);
+}
+
+#[actix_web::test]
+async fn los_errores_se_traducen_con_accept_language() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let credenciales = json!({ "email": "nadie@ejemplo.com", "password": CONTRASEÑA });
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .insert_header((header::ACCEPT_LANGUAGE, "en-GB,en;q=0.9"))
+        .set_json(&credenciales);
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 401);
+    assert_eq!(res.headers().get(header::CONTENT_LANGUAGE).unwrap(), "en");
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "Invalid credentials");
+    assert_eq!(cuerpo["code"], "auth.invalid_credentials");
+
+    // Sin cabecera, el idioma por defecto
+    let req = TestRequest::post().uri("/api/auth/login").set_json(&credenciales);
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.headers().get(header::CONTENT_LANGUAGE).unwrap(), "es");
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "Credenciales inválidas");
+    assert_eq!(cuerpo["code"], "auth.invalid_credentials");
+
+    // También los errores de los middlewares
+    let req = TestRequest::get().uri("/api/usuarios").insert_header((header::ACCEPT_LANGUAGE, "en"));
+    let res = test::call_service(&app, req.to_request()).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "Missing or invalid token");
+}
+
+#[actix_web::test]
+async fn los_errores_de_validacion_se_traducen_por_campo() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let mut datos = datos_registro();
+    datos["password"] = json!("corta");
+    let req = TestRequest::post()
+        .uri("/api/auth/registro")
+        .insert_header((header::ACCEPT_LANGUAGE, "en"))
+        .set_json(&datos);
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "Validation error");
+    let mensajes: Vec<&str> = cuerpo["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["message"].as_str().unwrap())
+        .collect();
+    assert!(mensajes.contains(&"The password must be at least 8 characters long"));
+}
+
+#[actix_web::test]
+async fn sin_accept_language_se_usa_el_idioma_del_usuario() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let mut datos = datos_registro();
+    datos["locale"] = json!("en-US");
+    let usuario = registrar(&app, &datos).await;
+    assert_eq!(usuario["locale"], "en");
+    let token = login(&app, datos["email"].as_str().unwrap(), CONTRASEÑA).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios/9999"), &token).await;
+    assert_eq!(res.headers().get(header::CONTENT_LANGUAGE).unwrap(), "en");
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "User not found");
+
+    // `Accept-Language` tiene prioridad sobre la preferencia guardada
+    let req = autenticada(TestRequest::get().uri("/api/usuarios/9999"), &token)
+        .insert_header((header::ACCEPT_LANGUAGE, "es"));
+    let res = test::call_service(&app, req.to_request()).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "Usuario no encontrado");
+}
+
+#[actix_web::test]
+async fn los_mensajes_de_exito_se_traducen() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::delete()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .insert_header((header::ACCEPT_LANGUAGE, "en"));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["message"], "User deleted successfully");
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/salud").to_request()).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["mensaje"], "Servidor funcionando correctamente");
+}
+
+#[actix_web::test]
+async fn el_idioma_preferido_debe_estar_soportado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .set_json(json!({ "locale": "fr" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "locale");
+    assert_eq!(cuerpo["errors"][0]["message"], "Idioma no soportado, usa es, en");
+}
@@ -0,0 +1,23 @@
+// Tests de integración de las rutas HTTP. Cada test construye la misma
+// aplicación que el binario sobre su propia base SQLite en memoria.
+mod support;
+
+mod audit;
+mod auth;
+mod cli;
+mod cors;
+mod envelope;
+mod health;
+mod i18n;
+mod metrics;
+mod naming;
+mod openapi;
+mod password_policy;
+mod profile;
+mod purge;
+mod server;
+#[cfg(feature = "tls")]
+mod tls;
+mod user_service;
+mod users;
+mod versioning;
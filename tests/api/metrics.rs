@@ -0,0 +1,54 @@
+use actix_web::test::{self, TestRequest};
+use rust_api::config::metrics::MetricsAccess;
+
+use super::support::Contexto;
+
+#[actix_web::test]
+async fn metricas_en_formato_prometheus() {
+    let mut ctx = Contexto::nuevo().await;
+    ctx.config.metricas = MetricsAccess::Publicas;
+    let app = ctx.app().await;
+
+    // Una solicitud previa para que haya al menos una serie HTTP
+    test::call_service(&app, TestRequest::get().uri("/api/salud").to_request()).await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/metrics").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo = test::read_body(res).await;
+    let texto = String::from_utf8_lossy(&cuerpo);
+    assert!(texto.contains("db_pool_connections"));
+}
+
+#[actix_web::test]
+async fn metricas_fuera_de_la_app_por_defecto_y_con_puerto_propio() {
+    let ctx = Contexto::nuevo().await;
+    assert_eq!(ctx.config.metricas, MetricsAccess::Deshabilitadas);
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/metrics").to_request()).await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+async fn metricas_con_token() {
+    let mut ctx = Contexto::nuevo().await;
+    ctx.config.metricas = MetricsAccess::Token("token-de-metricas".to_string());
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/metrics").to_request()).await;
+    assert_eq!(res.status(), 401);
+
+    for token in ["otro-token-de-metric", "token-de-metrica"] {
+        let req = TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", format!("Bearer {}", token)));
+        assert_eq!(test::call_service(&app, req.to_request()).await.status(), 401);
+    }
+
+    let req = TestRequest::get()
+        .uri("/metrics")
+        .insert_header(("Authorization", "Bearer token-de-metricas"));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 200);
+}
@@ -0,0 +1,148 @@
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use rust_api::config::field_case::FieldCase;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use super::support::{llamar_autenticada, registrar, usuario_con_token, Contexto, UsuarioFactory, CONTRASEÑA};
+
+#[derive(Serialize)]
+struct Pagina {
+    total_pages: u64,
+    items: Vec<Elemento>,
+    changes: Value,
+    componentes: BTreeMap<&'static str, Elemento>,
+}
+
+#[derive(Serialize)]
+struct Elemento {
+    deleted_at: Option<&'static str>,
+    name: &'static str,
+}
+
+#[test]
+fn camel_case_renombra_solo_los_campos_de_los_structs() {
+    let elemento = || Elemento { deleted_at: None, name: "ana_maria" };
+    let pagina = Pagina {
+        total_pages: 3,
+        items: vec![elemento()],
+        changes: json!({ "locale": { "from": null, "to": "en" }, "created_at": 1 }),
+        componentes: BTreeMap::from([("base_de_datos", elemento())]),
+    };
+
+    assert_eq!(
+        FieldCase::CamelCase.aplicar(&pagina).unwrap(),
+        json!({
+            "totalPages": 3,
+            "items": [{ "deletedAt": null, "name": "ana_maria" }],
+            "changes": { "locale": { "from": null, "to": "en" }, "created_at": 1 },
+            "componentes": { "base_de_datos": { "deletedAt": null, "name": "ana_maria" } }
+        })
+    );
+    assert_eq!(FieldCase::SnakeCase.aplicar(&pagina).unwrap(), serde_json::to_value(&pagina).unwrap());
+    assert_eq!(FieldCase::CamelCase.campo("emailTokenExpiresAt"), "email_token_expires_at");
+}
+
+#[actix_web::test]
+async fn los_nombres_en_espanol_se_aceptan_en_la_entrada() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let datos = json!({
+        "nombre": "Ana Pérez",
+        "correo": "ana.perez@ejemplo.com",
+        "contrasena": CONTRASEÑA,
+        "idioma": "en"
+    });
+    let usuario = registrar(&app, &datos).await;
+    assert_eq!(usuario["name"], "Ana Pérez");
+    assert_eq!(usuario["locale"], "en");
+
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "correo": "ana.perez@ejemplo.com", "contraseña": CONTRASEÑA }));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    let token = cuerpo["token"].as_str().unwrap();
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario["id"]))
+        .set_json(json!({ "nombre": "Ana María Pérez" }));
+    let res = llamar_autenticada(&app, req, token).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["name"], "Ana María Pérez");
+}
+
+#[actix_web::test]
+async fn la_convencion_de_campos_se_aplica_desde_la_v2() {
+    let mut ctx = Contexto::nuevo().await;
+    ctx.config.campos = FieldCase::CamelCase;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let uri = format!("/api/usuarios/{}", usuario.id);
+
+    let uri_v2 = format!("/api/v2/usuarios/{}", usuario.id);
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri_v2), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["data"]["createdAt"].is_string());
+    assert!(cuerpo["data"].get("created_at").is_none());
+    assert!(cuerpo["meta"]["requestId"].is_string());
+    assert_eq!(cuerpo["meta"]["apiVersion"], 2);
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/v2/usuarios/9999"), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["statusCode"], 404);
+
+    // La v1 conserva sus nombres
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["created_at"].is_string());
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios/9999"), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["status_code"], 404);
+}
+
+#[actix_web::test]
+async fn con_camel_case_la_entrada_y_los_errores_usan_los_mismos_nombres() {
+    let mut ctx = Contexto::nuevo().await;
+    ctx.config.campos = FieldCase::CamelCase;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    // Un cliente que devuelve lo que recibió
+    let req = TestRequest::patch()
+        .uri(&format!("/api/v2/usuarios/{}", usuario.id))
+        .insert_header(("Content-Type", "application/merge-patch+json"))
+        .set_payload(json!({ "createdAt": "2020-01-01T00:00:00Z", "emailToken": "x" }).to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+    let cuerpo: Value = test::read_body_json(res).await;
+    let mut campos: Vec<_> = cuerpo["errors"].as_array().unwrap().iter().map(|e| e["field"].clone()).collect();
+    campos.sort_by_key(|campo| campo.to_string());
+    assert_eq!(campos, [json!("createdAt"), json!("emailToken")]);
+    assert_eq!(cuerpo["errors"][0]["message"], "Este campo no se puede modificar");
+
+    let req = TestRequest::post()
+        .uri("/api/v2/perfil/contrasena")
+        .set_json(json!({ "currentPassword": CONTRASEÑA, "newPassword": "OtraSecreta456" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    let req = TestRequest::post()
+        .uri("/api/v2/perfil/contrasena")
+        .set_json(json!({ "currentPassword": "OtraSecreta456", "newPassword": "corta" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "newPassword");
+
+    // Las claves de los mapas son datos y no cambian
+    let req = TestRequest::get().uri("/api/salud/listo").insert_header((header::ACCEPT, "application/vnd.api+json;version=2"));
+    let res = test::call_service(&app, req.to_request()).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["data"]["componentes"]["base_de_datos"].is_object());
+    assert!(cuerpo["meta"]["requestId"].is_string());
+}
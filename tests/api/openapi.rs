@@ -0,0 +1,141 @@
+use std::collections::BTreeSet;
+
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::{Method, StatusCode};
+use actix_web::test::{self, TestRequest};
+use actix_web::{web, Error, HttpResponse};
+use rust_api::app::build_app;
+use rust_api::config::metrics::MetricsAccess;
+use rust_api::openapi::{operaciones, DOCUMENTO};
+use rust_api::routes::config::rutas;
+use serde_json::Value;
+
+use super::support::{autenticada, usuario_con_token, Contexto, UsuarioFactory};
+
+// Operaciones del documento como "MÉTODO /ruta", con la marca de si requieren token
+fn operaciones_documentadas() -> Vec<(String, String, bool)> {
+    DOCUMENTO
+        .paths
+        .paths
+        .iter()
+        .flat_map(|(ruta, item)| {
+            operaciones(item).map(move |(metodo, operacion)| {
+                let protegida = operacion.security.as_ref().is_some_and(|s| !s.is_empty());
+                (metodo.to_string(), ruta.clone(), protegida)
+            })
+        })
+        .collect()
+}
+
+// Ruta concreta para un patrón: cada `{parámetro}` pasa a ser `9999`
+fn ruta_concreta(patron: &str) -> String {
+    patron
+        .split('/')
+        .map(|segmento| if segmento.starts_with('{') { "9999" } else { segmento })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// La aplicación con un servicio por defecto reconocible: lo que no llega a
+// ninguna ruta responde 418 en vez del 404 de actix, que no se distingue del
+// 404 de un handler
+async fn app_con_marca(
+    ctx: &Contexto,
+) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = Error> {
+    test::init_service(build_app(&ctx.estado, &ctx.config).default_service(web::to(HttpResponse::ImATeapot))).await
+}
+
+// 418: ninguna ruta para la URL; 405: la URL es de un recurso, pero no con
+// ese método
+fn sin_ruta(estado: StatusCode) -> bool {
+    estado == StatusCode::IM_A_TEAPOT || estado == StatusCode::METHOD_NOT_ALLOWED
+}
+
+// Las rutas se registran desde las tablas `RUTAS` de `routes::*`: la misma
+// lista debe coincidir con el documento
+#[test]
+fn el_documento_cubre_todas_las_rutas() {
+    let documentadas: BTreeSet<String> = operaciones_documentadas()
+        .into_iter()
+        .map(|(metodo, ruta, _)| format!("{} {}", metodo, ruta))
+        .collect();
+    let registradas: BTreeSet<String> = rutas()
+        .into_iter()
+        .map(|(metodo, ruta)| format!("{} {}", metodo, ruta))
+        .collect();
+
+    let sin_documentar: Vec<_> = registradas.difference(&documentadas).collect();
+    let sin_registrar: Vec<_> = documentadas.difference(&registradas).collect();
+    assert!(sin_documentar.is_empty(), "rutas sin documentar en OpenAPI: {:?}", sin_documentar);
+    assert!(sin_registrar.is_empty(), "operaciones documentadas sin ruta: {:?}", sin_registrar);
+}
+
+#[actix_web::test]
+async fn las_operaciones_documentadas_existen_y_respetan_la_seguridad() {
+    let mut ctx = Contexto::nuevo().await;
+    // `/metrics` solo se sirve en el puerto de la API si se habilita
+    ctx.config.metricas = MetricsAccess::Publicas;
+    let app = app_con_marca(&ctx).await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    for (metodo, ruta, protegida) in operaciones_documentadas() {
+        let uri = ruta_concreta(&ruta);
+        let metodo = Method::from_bytes(metodo.as_bytes()).unwrap();
+
+        // Sin token: 401 solo en las operaciones con `security`
+        let req = TestRequest::default().method(metodo.clone()).uri(&uri).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status() == 401, protegida, "{} {}: seguridad documentada incorrecta", metodo, ruta);
+
+        // Con token, el router encuentra la ruta
+        let req = autenticada(TestRequest::default().method(metodo.clone()).uri(&uri), &token).to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(!sin_ruta(res.status()), "{} {} no está registrada", metodo, ruta);
+    }
+}
+
+#[actix_web::test]
+async fn el_documento_se_sirve_como_openapi_3_1() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/openapi.json").to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let documento: Value = test::read_body_json(res).await;
+    assert!(documento["openapi"].as_str().unwrap().starts_with("3.1"));
+    assert_eq!(documento["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+    assert!(documento["components"]["schemas"]["ErrorResponse"].is_object());
+    assert!(documento["components"]["schemas"]["User"]["properties"]["password"].is_null());
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/docs").to_request()).await;
+    assert_eq!(res.status(), 200);
+    let csp = res.headers().get("content-security-policy").unwrap().to_str().unwrap();
+    assert!(csp.contains("script-src 'self'"));
+    assert!(!csp.contains("https://"));
+}
+
+#[actix_web::test]
+async fn swagger_ui_se_sirve_desde_el_binario() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/docs").to_request()).await;
+    let pagina = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    assert!(!pagina.contains("https://"));
+
+    for (archivo, tipo) in [("swagger-ui.css", "text/css"), ("swagger-ui-bundle.js", "javascript")] {
+        assert!(pagina.contains(&format!("/api/docs/{}", archivo)));
+
+        let res = test::call_service(&app, TestRequest::get().uri(&format!("/api/docs/{}", archivo)).to_request()).await;
+        assert_eq!(res.status(), 200, "{}", archivo);
+        assert!(res.headers().get("content-type").unwrap().to_str().unwrap().contains(tipo));
+        assert!(!test::read_body(res).await.is_empty());
+    }
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/docs/index.html").to_request()).await;
+    assert_eq!(res.status(), 404);
+}
+
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use rust_api::utils::password_policy::PasswordPolicy;
+
+use super::support::{lista_de_filtradas, sha1_hex};
+
+// Solo la lista de filtradas: el resto de reglas, desactivadas
+fn politica(directorio: &Path) -> PasswordPolicy {
+    PasswordPolicy {
+        longitud_minima: 0,
+        requiere_mayuscula: false,
+        requiere_minuscula: false,
+        requiere_digito: false,
+        requiere_simbolo: false,
+        prohibir_datos_personales: false,
+        directorio_filtradas: Some(directorio.to_path_buf()),
+    }
+}
+
+fn filtrada(directorio: &Path, contraseña: &str) -> bool {
+    let errores = politica(directorio).validar(contraseña, "", "");
+    !errores.is_empty()
+}
+
+#[test]
+fn la_lista_de_filtradas_se_busca_por_prefijo_y_sufijo_del_sha1() {
+    let directorio = lista_de_filtradas(&["password1", "qwerty"]);
+
+    assert!(filtrada(&directorio, "password1"));
+    assert!(filtrada(&directorio, "qwerty"));
+    // Mismo archivo de prefijo no implica filtrada: el sufijo debe coincidir
+    assert!(!filtrada(&directorio, "Password1"));
+    assert!(!filtrada(&directorio, "otra-cualquiera"));
+
+    let errores = politica(&directorio).validar("password1", "", "");
+    assert_eq!(errores.len(), 1);
+    assert_eq!(errores[0].campo, "password");
+    assert_eq!(errores[0].mensaje.clave.codigo(), "password.breached");
+}
+
+#[test]
+fn el_archivo_de_rango_admite_los_formatos_de_have_i_been_pwned() {
+    let directorio = lista_de_filtradas(&[]);
+    let (prefijo, sufijo) = sha1_hex("password1");
+    let escribir = |nombre: &str, contenido: String| {
+        for archivo in std::fs::read_dir(&directorio).unwrap() {
+            std::fs::remove_file(archivo.unwrap().path()).unwrap();
+        }
+        std::fs::write(directorio.join(nombre), contenido).unwrap();
+    };
+
+    // Archivo sin extensión, con otras líneas y el sufijo en minúsculas
+    escribir(&prefijo, format!("0018A45C4D1DEF81644B54AB7F969B88D65:1\n{}:3\n", sufijo.to_lowercase()));
+    assert!(filtrada(&directorio, "password1"));
+
+    // Nombre de archivo en minúsculas y espacios alrededor del conteo
+    escribir(&format!("{}.txt", prefijo.to_lowercase()), format!("{}: 42 \r\n", sufijo));
+    assert!(filtrada(&directorio, "password1"));
+
+    // Sin conteo la línea cuenta como filtrada
+    escribir(&prefijo, format!("{}\n", sufijo));
+    assert!(filtrada(&directorio, "password1"));
+
+    // Las líneas de relleno llevan conteo 0
+    escribir(&prefijo, format!("{}:0\n", sufijo));
+    assert!(!filtrada(&directorio, "password1"));
+
+    // Un sufijo más largo o más corto no es el mismo hash
+    escribir(&prefijo, format!("{}0:5\n{}:5\n", sufijo, &sufijo[1..]));
+    assert!(!filtrada(&directorio, "password1"));
+}
+
+#[test]
+fn sin_archivo_para_el_prefijo_no_esta_filtrada() {
+    let directorio = lista_de_filtradas(&[]);
+    assert!(!filtrada(&directorio, "password1"));
+
+    // Un directorio inexistente no bloquea el alta: se avisa en el log
+    assert!(!filtrada(&directorio.join("no-existe"), "password1"));
+}
@@ -0,0 +1,217 @@
+use actix_web::test::{self, TestRequest};
+use rust_api::services::{Correo, EmailSender, FileEmailSender, InMemoryEmailSender};
+use serde_json::{json, Value};
+
+use super::support::{
+    llamar_autenticada, login, usuario_con_token, Contexto, UsuarioFactory, CONTRASEÑA, CONTRASEÑA_FILTRADA,
+};
+
+// Código del último correo enviado a `email`
+fn codigo_enviado(ctx: &Contexto, email: &str) -> String {
+    let correo = ctx
+        .correos
+        .enviados()
+        .into_iter()
+        .rev()
+        .find(|correo| correo.para == email)
+        .expect("no se envió ningún correo a ese email");
+
+    correo
+        .cuerpo
+        .split_whitespace()
+        .find(|palabra| palabra.len() == 64 && palabra.chars().all(|c| c.is_ascii_hexdigit()))
+        .expect("el correo no lleva el código")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn actualizar_nombre_del_perfil() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::patch().uri("/api/perfil").set_json(json!({ "nombre": "Nombre Nuevo" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["id"], usuario.id);
+    assert_eq!(cuerpo["name"], "Nombre Nuevo");
+    assert_eq!(cuerpo["email"], usuario.email);
+}
+
+#[actix_web::test]
+async fn cambiar_email_requiere_confirmacion() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let nuevo = format!("nuevo.{}", usuario.email);
+
+    let req = TestRequest::patch().uri("/api/perfil").set_json(json!({ "email": nuevo }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    // El email no cambia hasta confirmarlo
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["email"], usuario.email);
+    assert_eq!(cuerpo["pending_email"], nuevo);
+    login(&app, &usuario.email, CONTRASEÑA).await;
+
+    // Solo el dueño ve el email pendiente
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&format!("/api/usuarios/{}", usuario.id)), &token).await;
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo.get("pending_email").is_none());
+
+    let codigo = codigo_enviado(&ctx, &nuevo);
+
+    let req = TestRequest::post().uri("/api/perfil/email/confirmar").set_json(json!({ "token": "incorrecto" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let req = TestRequest::post().uri("/api/perfil/email/confirmar").set_json(json!({ "token": codigo }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["email"], nuevo);
+    assert_eq!(cuerpo["pending_email"], Value::Null);
+    login(&app, &nuevo, CONTRASEÑA).await;
+
+    // El código es de un solo uso
+    let req = TestRequest::post().uri("/api/perfil/email/confirmar").set_json(json!({ "token": codigo }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+}
+
+#[actix_web::test]
+async fn cambiar_email_a_uno_en_uso() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let otro = UsuarioFactory::new().crear(&ctx.db).await;
+
+    let req = TestRequest::patch().uri("/api/perfil").set_json(json!({ "email": otro.email }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 409);
+}
+
+#[actix_web::test]
+async fn cambiar_email_rechaza_direcciones_invalidas() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    for email in [
+        "nuevo@ejemplo.com\r\nBcc: otro@malicioso.com",
+        "nuevo@ejemplo.com\nBcc:otro@malicioso.com",
+        "nuevo @ejemplo.com",
+        "nuevo@ejemplo",
+        "nuevo@",
+        "@ejemplo.com",
+        "nuevo@@ejemplo.com",
+        "nuevo@ejemplo..com",
+        "nuevo@-ejemplo.com",
+        "<nuevo>@ejemplo.com",
+    ] {
+        let req = TestRequest::patch().uri("/api/perfil").set_json(json!({ "email": email }));
+        let res = llamar_autenticada(&app, req, &token).await;
+        assert_eq!(res.status(), 422, "{:?}", email);
+
+        let cuerpo: Value = test::read_body_json(res).await;
+        assert_eq!(cuerpo["errors"][0]["field"], "email", "{:?}", email);
+    }
+    assert!(ctx.correos.enviados().is_empty());
+}
+
+#[actix_web::test]
+async fn los_envios_rechazan_saltos_de_linea_en_las_cabeceras() {
+    let directorio = std::env::temp_dir().join(format!("correos-{}", std::process::id()));
+    let envios: [Box<dyn EmailSender>; 2] =
+        [Box::new(FileEmailSender::new(&directorio)), Box::new(InMemoryEmailSender::default())];
+
+    for envio in envios {
+        for (para, asunto) in [("a@ejemplo.com\r\nBcc: b@ejemplo.com", "Asunto"), ("a@ejemplo.com", "Asunto\nBcc: b@ejemplo.com")] {
+            let correo = Correo { para: para.to_string(), asunto: asunto.to_string(), cuerpo: String::new() };
+            let error = envio.enviar(correo).await.expect_err("se aceptó una cabecera con salto de línea");
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+    assert!(!directorio.exists() || std::fs::read_dir(&directorio).unwrap().next().is_none());
+}
+
+#[actix_web::test]
+async fn solo_un_administrador_cambia_el_email_sin_confirmar() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let (_, token_admin) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let uri = format!("/api/usuarios/{}", usuario.id);
+    let nuevo = format!("nuevo.{}", usuario.email);
+
+    let res = llamar_autenticada(&app, TestRequest::put().uri(&uri).set_json(json!({ "email": nuevo })), &token).await;
+    assert_eq!(res.status(), 422);
+
+    let req = TestRequest::patch()
+        .uri(&uri)
+        .insert_header(("Content-Type", "application/merge-patch+json"))
+        .set_payload(json!({ "email": nuevo }).to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let res = llamar_autenticada(&app, TestRequest::put().uri(&uri).set_json(json!({ "email": nuevo })), &token_admin).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["email"], nuevo);
+}
+
+#[actix_web::test]
+async fn cambiar_contraseña_exige_la_actual() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::post()
+        .uri("/api/perfil/contrasena")
+        .set_json(json!({ "current_password": "Incorrecta123", "new_password": "OtraSecreta456" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "current_password");
+
+    let req = TestRequest::post()
+        .uri("/api/perfil/contrasena")
+        .set_json(json!({ "current_password": CONTRASEÑA, "new_password": CONTRASEÑA_FILTRADA }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "new_password");
+    assert_eq!(cuerpo["errors"][0]["message"], "Esta contraseña aparece en filtraciones de datos conocidas, elige otra");
+
+    let req = TestRequest::post()
+        .uri("/api/perfil/contrasena")
+        .set_json(json!({ "contrasena_actual": CONTRASEÑA, "contrasena_nueva": "OtraSecreta456" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    login(&app, &usuario.email, "OtraSecreta456").await;
+}
+
+#[actix_web::test]
+async fn eliminar_cuenta_con_contraseña() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::delete().uri("/api/perfil").set_json(json!({ "password": "Incorrecta123" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let req = TestRequest::delete().uri("/api/perfil").set_json(json!({ "password": CONTRASEÑA }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    // El token de una cuenta eliminada deja de valer
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/perfil"), &token).await;
+    assert_eq!(res.status(), 401);
+}
@@ -0,0 +1,41 @@
+use chrono::{Duration, Utc};
+use rust_api::models::audit_log::{self, Entity as AuditLogEntity};
+use rust_api::models::user::{Entity as UserEntity, Model as UserModel};
+use rust_api::tasks::user_purge::purgar;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter, Set};
+
+use super::support::{Contexto, UsuarioFactory};
+
+async fn eliminado_hace(db: &DatabaseConnection, dias: i64) -> UserModel {
+    let usuario = UsuarioFactory::new().crear(db).await;
+    let mut modelo = usuario.into_active_model();
+    modelo.deleted_at = Set(Some(Utc::now() - Duration::days(dias)));
+    modelo.update(db).await.unwrap()
+}
+
+#[actix_web::test]
+async fn purgar_elimina_solo_los_usuarios_fuera_del_periodo_de_retencion() {
+    let ctx = Contexto::nuevo().await;
+    let activo = UsuarioFactory::new().crear(&ctx.db).await;
+    let reciente = eliminado_hace(&ctx.db, 29).await;
+    let antiguo = eliminado_hace(&ctx.db, 31).await;
+
+    assert_eq!(purgar(&ctx.db, 30).await.unwrap(), 1);
+
+    let ids: Vec<i32> = UserEntity::with_deleted().all(&ctx.db).await.unwrap().iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![activo.id, reciente.id]);
+
+    // Un evento de auditoría por usuario purgado
+    let eventos = AuditLogEntity::find()
+        .filter(audit_log::Column::Action.eq(audit_log::USER_PURGED))
+        .all(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(eventos.len(), 1);
+    assert_eq!(eventos[0].target_id, Some(antiguo.id));
+    assert_eq!(eventos[0].actor_id, None);
+
+    // Sin nada que purgar no se registra nada
+    assert_eq!(purgar(&ctx.db, 30).await.unwrap(), 0);
+    assert_eq!(AuditLogEntity::find().all(&ctx.db).await.unwrap().len(), 1);
+}
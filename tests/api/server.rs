@@ -0,0 +1,31 @@
+use std::net::TcpListener;
+
+use rust_api::{run, ServerSettings};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use super::support::Contexto;
+
+#[actix_web::test]
+async fn run_sirve_la_app_en_un_puerto_efimero() {
+    let ctx = Contexto::nuevo().await;
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let direccion = listener.local_addr().unwrap();
+
+    let servidor = run(ctx.estado.clone(), ServerSettings::new(ctx.config.clone()), listener).unwrap();
+    let handle = servidor.handle();
+    actix_web::rt::spawn(servidor);
+
+    let mut conexion = TcpStream::connect(direccion).await.unwrap();
+    conexion
+        .write_all(b"GET /api/salud HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut respuesta = String::new();
+    conexion.read_to_string(&mut respuesta).await.unwrap();
+
+    assert!(respuesta.starts_with("HTTP/1.1 200 OK"), "respuesta inesperada: {}", respuesta);
+    assert!(respuesta.to_lowercase().contains("x-request-id:"));
+
+    handle.stop(true).await;
+}
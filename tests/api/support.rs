@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use actix_web::web::Data;
+use actix_web::Error;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+
+use rust_api::app::{build_app, AppConfig, AppState};
+use rust_api::config::database;
+use rust_api::models::user::{self, Model as UserModel};
+use rust_api::repositories::SeaOrmUserRepository;
+use rust_api::services::{InMemoryEmailSender, UserService};
+use rust_api::utils::hash::hash_password;
+use rust_api::utils::lifecycle::Lifecycle;
+
+/// Contraseña que cumple la política por defecto; la usan todas las fábricas.
+pub const CONTRASEÑA: &str = "Secreta123";
+
+/// Cumple las reglas por defecto pero está en la lista de filtradas de los tests.
+pub const CONTRASEÑA_FILTRADA: &str = "Filtrada2024";
+
+static ENTORNO: Once = Once::new();
+static SECUENCIA: AtomicUsize = AtomicUsize::new(0);
+
+// Las variables de entorno son globales al proceso: se fijan una sola vez y
+// se quitan las que cambiarían el comportamiento entre tests
+pub fn preparar_entorno() {
+    ENTORNO.call_once(|| {
+        std::env::set_var("JWT_SECRET", "secreto-de-pruebas");
+        std::env::set_var("BCRYPT_COST", "4");
+        std::env::set_var("PASSWORD_BREACHED_DIR", lista_de_filtradas(&[CONTRASEÑA_FILTRADA]));
+        for variable in [
+            "JWT_KEYS_FILE",
+            "DATABASE_REPLICA_URL",
+            "REQUIRE_IF_MATCH",
+            "APP_ENV",
+            "DEFAULT_LOCALE",
+            "API_FIELD_CASE",
+            "EMAIL_OUTBOX_DIR",
+            "TRUSTED_PROXIES",
+            "METRICS_PORT",
+            "METRICS_TOKEN",
+            "METRICS_PUBLIC",
+        ] {
+            std::env::remove_var(variable);
+        }
+    });
+}
+
+/// Crea un directorio con el formato de rangos de Have I Been Pwned que
+/// contiene las contraseñas indicadas.
+pub fn lista_de_filtradas(contraseñas: &[&str]) -> PathBuf {
+    let directorio = std::env::temp_dir().join(format!("rust-api-filtradas-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&directorio).unwrap();
+
+    for contraseña in contraseñas {
+        let (prefijo, sufijo) = sha1_hex(contraseña);
+        let archivo = directorio.join(format!("{}.txt", prefijo));
+        let mut contenido = std::fs::read_to_string(&archivo).unwrap_or_default();
+        contenido.push_str(&format!("{}:12\r\n", sufijo));
+        std::fs::write(archivo, contenido).unwrap();
+    }
+
+    directorio
+}
+
+/// SHA-1 en hexadecimal en mayúsculas, separado en prefijo de 5 caracteres y sufijo.
+pub fn sha1_hex(contraseña: &str) -> (String, String) {
+    let hash: String = Sha1::digest(contraseña.as_bytes()).iter().map(|byte| format!("{:02X}", byte)).collect();
+    let (prefijo, sufijo) = hash.split_at(5);
+    (prefijo.to_string(), sufijo.to_string())
+}
+
+/// Base de datos aislada de un test y la configuración de la aplicación.
+pub struct Contexto {
+    pub db: DatabaseConnection,
+    pub estado: AppState,
+    pub config: AppConfig,
+    /// Correos enviados por la aplicación.
+    pub correos: Arc<InMemoryEmailSender>,
+}
+
+impl Contexto {
+    pub async fn nuevo() -> Self {
+        preparar_entorno();
+
+        let db = database::connect_to("sqlite::memory:")
+            .await
+            .expect("No se pudo crear la base de datos de pruebas");
+        let replica = database::connect_replica(&db)
+            .await
+            .expect("No se pudo conectar la réplica de pruebas");
+
+        let correos = Arc::new(InMemoryEmailSender::default());
+        let mut estado = AppState::new(db.clone(), replica.clone(), Data::new(Lifecycle::default()));
+        estado.usuarios = Data::new(
+            UserService::new(SeaOrmUserRepository::new(db.clone(), replica)).con_correo(correos.clone()),
+        );
+
+        Self {
+            estado,
+            config: AppConfig::from_env(),
+            db,
+            correos,
+        }
+    }
+
+    /// Inicializa la aplicación completa (rutas y middlewares) para este contexto.
+    pub async fn app(
+        &self,
+    ) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = Error> {
+        test::init_service(build_app(&self.estado, &self.config)).await
+    }
+}
+
+/// Fábrica de usuarios que se insertan directamente en la base de datos.
+pub struct UsuarioFactory {
+    nombre: String,
+    email: String,
+    contraseña: String,
+    admin: bool,
+}
+
+impl UsuarioFactory {
+    pub fn new() -> Self {
+        let n = SECUENCIA.fetch_add(1, Ordering::Relaxed);
+        Self {
+            nombre: format!("Usuario {}", n),
+            email: format!("usuario{}@ejemplo.com", n),
+            contraseña: CONTRASEÑA.to_string(),
+            admin: false,
+        }
+    }
+
+    pub fn nombre(mut self, nombre: &str) -> Self {
+        self.nombre = nombre.to_string();
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = email.to_string();
+        self
+    }
+
+    pub fn admin(mut self) -> Self {
+        self.admin = true;
+        self
+    }
+
+    pub async fn crear(self, db: &DatabaseConnection) -> UserModel {
+        let rol = if self.admin { user::ROLE_ADMIN } else { user::ROLE_USER };
+
+        user::ActiveModel {
+            name: Set(self.nombre),
+            email: Set(self.email),
+            password: Set(hash_password(&self.contraseña).expect("No se pudo cifrar la contraseña")),
+            role: Set(rol.to_string()),
+            version: Set(1),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("No se pudo crear el usuario de pruebas")
+    }
+}
+
+/// Datos de registro únicos para `POST /api/auth/registro`.
+pub fn datos_registro() -> Value {
+    let n = SECUENCIA.fetch_add(1, Ordering::Relaxed);
+    json!({
+        "name": format!("Registrado {}", n),
+        "email": format!("registrado{}@ejemplo.com", n),
+        "password": CONTRASEÑA
+    })
+}
+
+/// Registra un usuario por la API y devuelve el usuario creado.
+pub async fn registrar<S, B>(app: &S, datos: &Value) -> Value
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = TestRequest::post().uri("/api/auth/registro").set_json(datos).to_request();
+    let res = test::call_service(app, req).await;
+    assert_eq!(res.status(), 201, "el registro debería crear el usuario");
+    test::read_body_json(res).await
+}
+
+/// Inicia sesión por la API y devuelve el token.
+pub async fn login<S, B>(app: &S, email: &str, contraseña: &str) -> String
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": email, "password": contraseña }))
+        .to_request();
+    let res = test::call_service(app, req).await;
+    assert_eq!(res.status(), 200, "el login debería ser correcto");
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    cuerpo["token"].as_str().expect("respuesta de login sin token").to_string()
+}
+
+/// Crea un usuario con la fábrica e inicia sesión con él.
+pub async fn usuario_con_token<S, B>(app: &S, db: &DatabaseConnection, fabrica: UsuarioFactory) -> (UserModel, String)
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let usuario = fabrica.crear(db).await;
+    let token = login(app, &usuario.email, CONTRASEÑA).await;
+    (usuario, token)
+}
+
+/// Añade la cabecera `Authorization: Bearer` a la solicitud.
+pub fn autenticada(req: TestRequest, token: &str) -> TestRequest {
+    req.insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+}
+
+/// Hace una solicitud autenticada y devuelve la respuesta.
+pub async fn llamar_autenticada<S, B>(app: &S, req: TestRequest, token: &str) -> ServiceResponse<B>
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    test::call_service(app, autenticada(req, token).to_request()).await
+}
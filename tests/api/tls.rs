@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rust_api::config::tls::TlsSettings;
+
+fn ajustes(variables: &[(&str, &str)]) -> Result<Option<TlsSettings>, String> {
+    let variables: HashMap<String, String> =
+        variables.iter().map(|(nombre, valor)| (nombre.to_string(), valor.to_string())).collect();
+    TlsSettings::desde(|nombre| variables.get(nombre).cloned())
+}
+
+#[test]
+fn sin_certificado_ni_clave_no_hay_tls() {
+    assert!(ajustes(&[]).unwrap().is_none());
+}
+
+#[test]
+fn certificado_y_clave_activan_tls() {
+    let tls = ajustes(&[("TLS_CERT_PATH", "cert.pem"), ("TLS_KEY_PATH", "clave.pem"), ("TLS_REDIRECT_PORT", "8000")])
+        .unwrap()
+        .unwrap();
+    assert_eq!(tls.puerto_redireccion, Some(8000));
+    assert_eq!(tls.intervalo_recarga, Duration::from_secs(30));
+}
+
+#[test]
+fn configuraciones_incompletas_o_invalidas_son_un_error() {
+    for variables in [
+        vec![("TLS_CERT_PATH", "cert.pem")],
+        vec![("TLS_KEY_PATH", "clave.pem")],
+        vec![("TLS_CERT_PATH", "cert.pem"), ("TLS_KEY_PATH", "clave.pem"), ("TLS_REDIRECT_PORT", "http")],
+        vec![("TLS_CERT_PATH", "cert.pem"), ("TLS_KEY_PATH", "clave.pem"), ("TLS_REDIRECT_PORT", "0")],
+        vec![("TLS_CERT_PATH", "cert.pem"), ("TLS_KEY_PATH", "clave.pem"), ("TLS_RELOAD_INTERVAL_SECS", "0")],
+        vec![("TLS_CERT_PATH", "cert.pem"), ("TLS_KEY_PATH", "clave.pem"), ("TLS_RELOAD_INTERVAL_SECS", "-5")],
+    ] {
+        assert!(ajustes(&variables).is_err(), "{:?}", variables);
+    }
+}
@@ -0,0 +1,221 @@
+use actix_web::test::{self, TestRequest};
+use actix_web::{web, web::Data, App};
+use rust_api::config::metrics::MetricsAccess;
+use rust_api::errors::api_error::ApiError;
+use rust_api::middleware::auth::Authentication;
+use rust_api::models::user::{CreateUserDto, UpdateProfileDto, UpdateUserDto, ROLE_ADMIN, ROLE_USER};
+use rust_api::repositories::InMemoryUserRepository;
+use rust_api::routes;
+use rust_api::services::{Autenticacion, UserService};
+use rust_api::utils::jwt::{generar_token, Claims};
+use serde_json::{json, Value};
+
+use super::support::{autenticada, preparar_entorno, CONTRASEÑA, CONTRASEÑA_FILTRADA};
+
+fn servicio() -> UserService {
+    preparar_entorno();
+    UserService::new(InMemoryUserRepository::default())
+}
+
+fn datos(nombre: &str, email: &str) -> CreateUserDto {
+    CreateUserDto {
+        name: nombre.to_string(),
+        email: email.to_string(),
+        password: CONTRASEÑA.to_string(),
+        locale: None,
+    }
+}
+
+fn codigo<T: std::fmt::Debug>(resultado: Result<T, ApiError>) -> u16 {
+    resultado.expect_err("se esperaba un error").codigo_estado
+}
+
+#[actix_web::test]
+async fn registrar_cifra_la_contraseña() {
+    let usuarios = servicio();
+
+    let usuario = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    assert_ne!(usuario.password, CONTRASEÑA);
+    assert_eq!(usuario.version, 1);
+    assert!(matches!(
+        usuarios.autenticar("ana@ejemplo.com", CONTRASEÑA).await.unwrap(),
+        Autenticacion::Valida(_)
+    ));
+}
+
+#[actix_web::test]
+async fn registrar_exige_email_unico() {
+    let usuarios = servicio();
+    usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+
+    let resultado = usuarios.registrar(&datos("Otra Ana", "ana@ejemplo.com"), ROLE_USER).await;
+    assert_eq!(codigo(resultado), 409);
+}
+
+#[actix_web::test]
+async fn registrar_aplica_la_politica_de_contraseñas() {
+    let usuarios = servicio();
+    let mut datos = datos("Ana", "ana@ejemplo.com");
+    datos.password = "corta".to_string();
+
+    assert_eq!(codigo(usuarios.registrar(&datos, ROLE_USER).await), 422);
+}
+
+// La misma política en el alta, en el cambio por un administrador y en el
+// cambio desde el perfil, con los errores en el campo de cada solicitud
+#[actix_web::test]
+async fn los_cambios_de_contraseña_aplican_la_politica() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana Torres", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+
+    let mut filtrada = datos("Luis", "luis@ejemplo.com");
+    filtrada.password = CONTRASEÑA_FILTRADA.to_string();
+    let error = usuarios.registrar(&filtrada, ROLE_USER).await.unwrap_err();
+    assert_eq!(error.errores[0].mensaje.clave.codigo(), "password.breached");
+
+    for (contraseña, regla) in [
+        ("corta1A", "password.too_short"),
+        (CONTRASEÑA_FILTRADA, "password.breached"),
+        ("Torres2024", "password.personal_data"),
+    ] {
+        let cambios = UpdateUserDto { name: None, email: None, password: Some(contraseña.to_string()), locale: None };
+        let error = usuarios.actualizar(ana.clone(), &cambios, true).await.unwrap_err();
+        assert_eq!(error.codigo_estado, 422);
+        assert_eq!((error.errores[0].campo.as_str(), error.errores[0].mensaje.clave.codigo()), ("password", regla));
+
+        let error = usuarios.cambiar_contraseña(ana.clone(), CONTRASEÑA, contraseña).await.unwrap_err();
+        assert_eq!(error.codigo_estado, 422);
+        assert_eq!((error.errores[0].campo.as_str(), error.errores[0].mensaje.clave.codigo()), ("new_password", regla));
+    }
+
+    usuarios.cambiar_contraseña(ana, CONTRASEÑA, "OtraSecreta456").await.unwrap();
+}
+
+#[actix_web::test]
+async fn autenticar_distingue_los_casos() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+
+    assert!(matches!(
+        usuarios.autenticar("ana@ejemplo.com", "Incorrecta123").await.unwrap(),
+        Autenticacion::Invalida(Some(id)) if id == ana.id
+    ));
+    assert!(matches!(
+        usuarios.autenticar("nadie@ejemplo.com", CONTRASEÑA).await.unwrap(),
+        Autenticacion::Invalida(None)
+    ));
+}
+
+#[actix_web::test]
+async fn actualizar_detecta_escrituras_concurrentes() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    let cambios = UpdateUserDto { name: Some("Ana María".to_string()), email: None, password: None, locale: None };
+
+    let actualizada = usuarios.actualizar(ana.clone(), &cambios, false).await.unwrap();
+    assert_eq!(actualizada.name, "Ana María");
+    assert_eq!(actualizada.version, 2);
+
+    // `ana` es la versión 1, ya sobrescrita
+    assert_eq!(codigo(usuarios.actualizar(ana, &cambios, false).await), 412);
+}
+
+#[actix_web::test]
+async fn actualizar_no_permite_un_email_en_uso() {
+    let usuarios = servicio();
+    usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    let luis = usuarios.registrar(&datos("Luis", "luis@ejemplo.com"), ROLE_USER).await.unwrap();
+    let cambios = UpdateUserDto { name: None, email: Some("ana@ejemplo.com".to_string()), password: None, locale: None };
+
+    assert_eq!(codigo(usuarios.actualizar(luis, &cambios, true).await), 409);
+}
+
+#[actix_web::test]
+async fn cambiar_el_email_del_perfil_requiere_envio_de_correo() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    let cambios = UpdateProfileDto { name: None, email: Some("ana.nueva@ejemplo.com".to_string()), locale: None };
+
+    assert_eq!(codigo(usuarios.actualizar_perfil(ana.clone(), &cambios).await), 503);
+    // Sin el correo no queda nada pendiente
+    assert_eq!(usuarios.buscar(ana.id).await.unwrap().pending_email, None);
+}
+
+#[actix_web::test]
+async fn parchear_solo_campos_editables() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+
+    let mut documento = serde_json::to_value(&ana).unwrap();
+    documento["role"] = json!(ROLE_ADMIN);
+    let error = usuarios.parchear(ana.clone(), &documento, false).await.unwrap_err();
+    assert_eq!(error.codigo_estado, 422);
+    assert_eq!(error.errores[0].campo, "role");
+
+    let mut documento = serde_json::to_value(&ana).unwrap();
+    documento["name"] = json!("Ana Parcheada");
+    assert_eq!(usuarios.parchear(ana, &documento, false).await.unwrap().name, "Ana Parcheada");
+}
+
+#[actix_web::test]
+async fn eliminar_y_restaurar() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+
+    usuarios.eliminar(ana.clone()).await.unwrap();
+    assert_eq!(codigo(usuarios.obtener(ana.id).await), 404);
+    assert!(usuarios.listar().await.unwrap().is_empty());
+
+    // Mientras está eliminada, otro usuario puede ocupar su email
+    let otra = usuarios.registrar(&datos("Otra Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    assert_eq!(codigo(usuarios.restaurar(ana.id).await), 409);
+
+    usuarios.eliminar(otra).await.unwrap();
+    let restaurada = usuarios.restaurar(ana.id).await.unwrap();
+    assert!(restaurada.deleted_at.is_none());
+    assert_eq!(codigo(usuarios.restaurar(ana.id).await), 400);
+}
+
+#[actix_web::test]
+async fn requerir_admin_comprueba_el_rol() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    let admin = usuarios.registrar(&datos("Admin", "admin@ejemplo.com"), ROLE_ADMIN).await.unwrap();
+    let claims = |id: i32| Claims { sub: id.to_string(), exp: usize::MAX };
+
+    assert_eq!(codigo(usuarios.requerir_admin(&claims(ana.id)).await), 403);
+    assert_eq!(usuarios.requerir_admin(&claims(admin.id)).await.unwrap().id, admin.id);
+}
+
+// Los handlers de lectura solo dependen del servicio: se prueban sin base de datos
+#[actix_web::test]
+async fn handlers_con_repositorio_en_memoria() {
+    let usuarios = servicio();
+    let ana = usuarios.registrar(&datos("Ana", "ana@ejemplo.com"), ROLE_USER).await.unwrap();
+    let token = generar_token(ana.id.to_string()).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(usuarios))
+            .wrap(Authentication::new(MetricsAccess::default()))
+            .service(
+                web::scope("/api")
+                    .configure(routes::user_routes::config)
+                    .configure(routes::profile_routes::config),
+            ),
+    )
+    .await;
+
+    let res = test::call_service(&app, autenticada(TestRequest::get().uri("/api/usuarios"), &token).to_request()).await;
+    assert_eq!(res.status(), 200);
+    let lista: Vec<Value> = test::read_body_json(res).await;
+    assert_eq!(lista.len(), 1);
+
+    let uri = format!("/api/usuarios/{}", ana.id);
+    let res = test::call_service(&app, autenticada(TestRequest::get().uri(&uri), &token).to_request()).await;
+    assert_eq!(res.status(), 200);
+
+    let res = test::call_service(&app, autenticada(TestRequest::get().uri("/api/perfil"), &token).to_request()).await;
+    let perfil: Value = test::read_body_json(res).await;
+    assert_eq!(perfil["datos"]["email"], "ana@ejemplo.com");
+}
@@ -0,0 +1,337 @@
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use chrono::Utc;
+use rust_api::models::user::Entity as UserEntity;
+use sea_orm::{ActiveModelTrait, IntoActiveModel, Set};
+use serde_json::{json, Value};
+
+use super::support::{llamar_autenticada, usuario_con_token, Contexto, UsuarioFactory};
+
+#[actix_web::test]
+async fn listar_requiere_token() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/usuarios").to_request()).await;
+    assert_eq!(res.status(), 401);
+}
+
+#[actix_web::test]
+async fn listar_excluye_eliminados() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let otro = UsuarioFactory::new().crear(&ctx.db).await;
+
+    let res = llamar_autenticada(&app, TestRequest::delete().uri(&format!("/api/usuarios/{}", otro.id)), &token).await;
+    assert_eq!(res.status(), 200);
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios"), &token).await;
+    assert_eq!(res.status(), 200);
+
+    let usuarios: Vec<Value> = test::read_body_json(res).await;
+    assert_eq!(usuarios.len(), 1);
+    assert!(usuarios.iter().all(|u| u["id"] != otro.id));
+}
+
+#[actix_web::test]
+async fn las_consultas_excluyen_eliminados_por_defecto() {
+    let ctx = Contexto::nuevo().await;
+    let activo = UsuarioFactory::new().crear(&ctx.db).await;
+    let eliminado = UsuarioFactory::new().crear(&ctx.db).await;
+    let mut modelo = eliminado.clone().into_active_model();
+    modelo.deleted_at = Set(Some(Utc::now()));
+    modelo.update(&ctx.db).await.unwrap();
+
+    let ids: Vec<i32> = UserEntity::find().all(&ctx.db).await.unwrap().iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![activo.id]);
+    assert!(UserEntity::find_by_id(eliminado.id).one(&ctx.db).await.unwrap().is_none());
+
+    assert_eq!(UserEntity::with_deleted().all(&ctx.db).await.unwrap().len(), 2);
+    assert!(UserEntity::find_by_id_with_deleted(eliminado.id).one(&ctx.db).await.unwrap().is_some());
+}
+
+#[actix_web::test]
+async fn obtener_por_id_con_etag() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let uri = format!("/api/usuarios/{}", usuario.id);
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+    assert_eq!(res.status(), 200);
+    let etag = res.headers().get(header::ETAG).unwrap().clone();
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["email"], usuario.email);
+
+    let req = TestRequest::get().uri(&uri).insert_header((header::IF_NONE_MATCH, etag));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 304);
+}
+
+#[actix_web::test]
+async fn las_respuestas_no_incluyen_el_hash_de_la_contraseña() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    for uri in [
+        "/api/v1/usuarios".to_string(),
+        "/api/v2/usuarios".to_string(),
+        format!("/api/v1/usuarios/{}", usuario.id),
+        format!("/api/v2/usuarios/{}", usuario.id),
+        "/api/v1/perfil".to_string(),
+        "/api/v2/perfil".to_string(),
+    ] {
+        let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token).await;
+        assert_eq!(res.status(), 200, "{}", uri);
+
+        let cuerpo = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+        assert!(!cuerpo.contains("\"password\""), "{} devuelve la contraseña", uri);
+        assert!(!cuerpo.contains(&usuario.password), "{} devuelve el hash", uri);
+    }
+}
+
+#[actix_web::test]
+async fn obtener_usuario_inexistente() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri("/api/usuarios/9999"), &token).await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+async fn actualizar_con_put() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .set_json(json!({ "name": "Nombre Nuevo" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers().get(header::ETAG).unwrap(), &format!("\"{}-2\"", usuario.id));
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["name"], "Nombre Nuevo");
+    assert_eq!(cuerpo["version"], 2);
+}
+
+#[actix_web::test]
+async fn actualizar_contraseña_aplica_la_politica() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let usuario = UsuarioFactory::new().crear(&ctx.db).await;
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .set_json(json!({ "password": "sinmayusculas1" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "password");
+    assert_eq!(cuerpo["errors"][0]["message"], "La contraseña debe incluir al menos una letra mayúscula");
+}
+
+#[actix_web::test]
+async fn solo_el_propio_usuario_o_un_administrador_modifica_un_usuario() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+    let otro = UsuarioFactory::new().crear(&ctx.db).await;
+    let uri = format!("/api/usuarios/{}", otro.id);
+
+    let res = llamar_autenticada(&app, TestRequest::put().uri(&uri).set_json(json!({ "password": "Distinta123!" })), &token).await;
+    assert_eq!(res.status(), 403);
+
+    let req = TestRequest::patch()
+        .uri(&uri)
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "name": "Otro" }).to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 403);
+
+    let res = llamar_autenticada(&app, TestRequest::delete().uri(&uri), &token).await;
+    assert_eq!(res.status(), 403);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["code"], "auth.owner_required");
+
+    // Sin cambios en el usuario
+    let (_, token_admin) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token_admin).await;
+    assert_eq!(res.status(), 200);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["name"], otro.name);
+    assert_eq!(cuerpo["version"], 1);
+}
+
+#[actix_web::test]
+async fn la_contraseña_propia_se_cambia_desde_el_perfil() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .set_json(json!({ "password": "Distinta123!" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "password");
+    assert_eq!(
+        cuerpo["errors"][0]["message"],
+        "La contraseña se cambia desde /api/perfil/contrasena con la contraseña actual"
+    );
+}
+
+#[actix_web::test]
+async fn actualizar_con_if_match_obsoleto() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::put()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .insert_header((header::IF_MATCH, format!("\"{}-0\"", usuario.id)))
+        .set_json(json!({ "name": "Nombre Nuevo" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 412);
+}
+
+#[actix_web::test]
+async fn modificar_con_merge_patch() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::patch()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "name": "Con Merge Patch" }).to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["name"], "Con Merge Patch");
+    assert_eq!(cuerpo["email"], usuario.email);
+}
+
+#[actix_web::test]
+async fn modificar_con_json_patch() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    // Solo un administrador cambia el email sin confirmarlo
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let usuario = UsuarioFactory::new().crear(&ctx.db).await;
+
+    let parche = json!([
+        { "op": "test", "path": "/name", "value": usuario.name },
+        { "op": "replace", "path": "/email", "value": "nuevo@ejemplo.com" }
+    ]);
+    let req = TestRequest::patch()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .insert_header((header::CONTENT_TYPE, "application/json-patch+json"))
+        .set_payload(parche.to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["email"], "nuevo@ejemplo.com");
+}
+
+#[actix_web::test]
+async fn modificar_campo_protegido() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::patch()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+        .set_payload(json!({ "role": "admin" }).to_string());
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 422);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert_eq!(cuerpo["errors"][0]["field"], "role");
+
+    // Los campos que no salen en las respuestas también son de solo lectura
+    for campo in ["password", "email_token"] {
+        let req = TestRequest::patch()
+            .uri(&format!("/api/usuarios/{}", usuario.id))
+            .insert_header((header::CONTENT_TYPE, "application/merge-patch+json"))
+            .set_payload(json!({ campo: "valor" }).to_string());
+        let res = llamar_autenticada(&app, req, &token).await;
+        assert_eq!(res.status(), 422);
+
+        let cuerpo: Value = test::read_body_json(res).await;
+        assert_eq!(cuerpo["errors"][0]["field"], campo);
+        assert_eq!(cuerpo["errors"][0]["message"], "Este campo no se puede modificar");
+    }
+}
+
+#[actix_web::test]
+async fn modificar_con_content_type_no_soportado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::patch()
+        .uri(&format!("/api/usuarios/{}", usuario.id))
+        .set_json(json!({ "name": "Otro" }));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 415);
+}
+
+#[actix_web::test]
+async fn eliminar_y_restaurar() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token_admin) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+    let usuario = UsuarioFactory::new().crear(&ctx.db).await;
+    let uri = format!("/api/usuarios/{}", usuario.id);
+
+    let res = llamar_autenticada(&app, TestRequest::delete().uri(&uri), &token_admin).await;
+    assert_eq!(res.status(), 200);
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token_admin).await;
+    assert_eq!(res.status(), 404);
+
+    let res = llamar_autenticada(&app, TestRequest::post().uri(&format!("{}/restaurar", uri)), &token_admin).await;
+    assert_eq!(res.status(), 200);
+
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["deleted_at"].is_null());
+
+    let res = llamar_autenticada(&app, TestRequest::get().uri(&uri), &token_admin).await;
+    assert_eq!(res.status(), 200);
+}
+
+#[actix_web::test]
+async fn restaurar_usuario_no_eliminado() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (admin, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new().admin()).await;
+
+    let req = TestRequest::post().uri(&format!("/api/usuarios/{}/restaurar", admin.id));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+async fn restaurar_requiere_administrador() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (usuario, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    let req = TestRequest::post().uri(&format!("/api/usuarios/{}/restaurar", usuario.id));
+    let res = llamar_autenticada(&app, req, &token).await;
+    assert_eq!(res.status(), 403);
+}
@@ -0,0 +1,97 @@
+use actix_web::http::header;
+use actix_web::test::{self, TestRequest};
+use chrono::{TimeZone, Utc};
+use rust_api::config::api_version::{ApiVersionSettings, Obsolescencia};
+use serde_json::Value;
+
+use super::support::{autenticada, datos_registro, usuario_con_token, Contexto, UsuarioFactory};
+
+const ACEPTA_V2: &str = "application/vnd.api+json;version=2";
+
+#[actix_web::test]
+async fn las_rutas_se_sirven_en_cada_version() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+    let (_, token) = usuario_con_token(&app, &ctx.db, UsuarioFactory::new()).await;
+
+    for uri in ["/api/perfil", "/api/v1/perfil", "/api/v2/perfil"] {
+        let res = test::call_service(&app, autenticada(TestRequest::get().uri(uri), &token).to_request()).await;
+        assert_eq!(res.status(), 200, "{}", uri);
+    }
+
+    // Las rutas públicas siguen siéndolo con versión
+    let req = TestRequest::post().uri("/api/v2/auth/registro").set_json(datos_registro());
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 201);
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/v3/info").to_request()).await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+async fn las_rutas_sin_version_van_a_la_v1() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/info").to_request()).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers().get("api-version").unwrap(), "1");
+    assert!(res.headers().get_all(header::VARY).any(|valor| valor == "Accept"));
+
+    // Sondas y documentación no tienen rutas por versión, pero negocian el formato
+    let req = TestRequest::get().uri("/api/salud").insert_header((header::ACCEPT, ACEPTA_V2));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.headers().get("api-version").unwrap(), "2");
+    let res = test::call_service(&app, TestRequest::get().uri("/api/v1/salud").to_request()).await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+async fn la_version_se_negocia_con_accept() {
+    let ctx = Contexto::nuevo().await;
+    let app = ctx.app().await;
+
+    let req = TestRequest::get().uri("/api/info?detalle=1").insert_header((header::ACCEPT, ACEPTA_V2));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers().get("api-version").unwrap(), "2");
+
+    // Los demás tipos de `Accept` no cambian la versión por defecto
+    let req = TestRequest::get().uri("/api/info").insert_header((header::ACCEPT, "application/json"));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.headers().get("api-version").unwrap(), "1");
+
+    let req = TestRequest::get()
+        .uri("/api/info")
+        .insert_header((header::ACCEPT, "application/vnd.api+json; version=7"));
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), 406);
+    let cuerpo: Value = test::read_body_json(res).await;
+    assert!(cuerpo["message"].as_str().unwrap().contains("Versiones disponibles: 1, 2"));
+}
+
+#[actix_web::test]
+async fn las_versiones_obsoletas_lo_anuncian() {
+    let mut ctx = Contexto::nuevo().await;
+    ctx.config.versiones = ApiVersionSettings {
+        obsoletas: vec![Obsolescencia {
+            version: 1,
+            desde: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            retirada: Some(Utc.with_ymd_and_hms(2027, 6, 30, 0, 0, 0).unwrap()),
+        }],
+        enlace: Some("https://ejemplo.com/migracion-v2".to_string()),
+    };
+    let app = ctx.app().await;
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/info").to_request()).await;
+    assert_eq!(res.headers().get("deprecation").unwrap(), "@1767225600");
+    assert_eq!(res.headers().get("sunset").unwrap(), "Wed, 30 Jun 2027 00:00:00 GMT");
+    assert_eq!(
+        res.headers().get(header::LINK).unwrap(),
+        "<https://ejemplo.com/migracion-v2>; rel=\"deprecation\""
+    );
+
+    let res = test::call_service(&app, TestRequest::get().uri("/api/v2/info").to_request()).await;
+    assert!(res.headers().get("deprecation").is_none());
+    assert!(res.headers().get("sunset").is_none());
+}